@@ -0,0 +1,332 @@
+//! Claim bookkeeping and payouts shared by `claim`, `claim_many` and `crank_claims`.
+
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::{create, Create};
+use anchor_spl::token_2022::{
+    spl_token_2022::{
+        self,
+        extension::StateWithExtensions,
+        state::{Account as Token2022Account, AccountState},
+    },
+    TransferChecked,
+};
+use anchor_spl::token_interface::{self, Mint as SplMint};
+
+use crate::{
+    errors::ErrorCode,
+    events::ClaimEvent,
+    state::{GlobalConfig, UserPosition, UserSummary},
+    token_extensions::{amount_with_transfer_fee, transfer_checked_with_hook},
+    utils::to_pay_amount,
+};
+
+/// What claiming a position at a given time releases.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PositionClaim {
+    /// Vested amount, the position's `claimed_amount` once the claim is booked.
+    pub claimed_after: u64,
+    pub walien_amount: u64,
+    /// Proceeds in quote units. They follow the vested share, so the full `usdc_spent` is
+    /// forwarded on the last claim.
+    pub usdc_amount: u64,
+}
+
+impl PositionClaim {
+    pub fn compute(cfg: &GlobalConfig, position: &UserPosition, now_ts: i64) -> Result<Self> {
+        let claimed_before = position.claimed_amount;
+        let vested = cfg.vested_amount(position.walien_allocation, now_ts)?;
+        if vested <= claimed_before {
+            return Ok(Self {
+                claimed_after: claimed_before,
+                walien_amount: 0,
+                usdc_amount: 0,
+            });
+        }
+        Ok(Self {
+            claimed_after: vested,
+            walien_amount: vested - claimed_before,
+            usdc_amount: position.usdc_released(vested)?
+                - position.usdc_released(claimed_before)?,
+        })
+    }
+
+    /// Books the claim on `position` and its owner's `summary`. Returns whether the position
+    /// is now fully claimed, in which case the caller closes it.
+    pub fn book(&self, position: &mut UserPosition, summary: &mut UserSummary) -> Result<bool> {
+        summary.total_walien_alloc = summary
+            .total_walien_alloc
+            .checked_sub(self.walien_amount)
+            .ok_or(ErrorCode::UserSummaryUnderflow)?;
+        summary.total_usdc_locked = summary
+            .total_usdc_locked
+            .checked_sub(self.usdc_amount)
+            .ok_or(ErrorCode::UserSummaryUnderflow)?;
+
+        position.claimed_amount = self.claimed_after;
+        let fully_claimed = self.claimed_after == position.walien_allocation;
+        if fully_claimed {
+            position.walien_allocation = 0;
+        }
+        Ok(fully_claimed)
+    }
+
+    /// Event for this claim, built before `book` so that it carries the allocation.
+    pub fn event(
+        &self,
+        pool_id: u64,
+        caller: Pubkey,
+        user: Pubkey,
+        user_position: Pubkey,
+        position: &UserPosition,
+    ) -> ClaimEvent {
+        ClaimEvent {
+            pool_id,
+            caller,
+            user,
+            user_position,
+            position_index: position.index,
+            walien_amount: self.walien_amount,
+            usdc_amount: self.usdc_amount,
+            claimed_amount: self.claimed_after,
+            walien_allocation: position.walien_allocation,
+        }
+    }
+}
+
+/// Whether `user`'s Walien ATA already exists. An existing one must be a usable Token-2022
+/// account of `mint` owned by `user`.
+pub fn walien_ata_exists(
+    token_account: &AccountInfo,
+    mint: &Pubkey,
+    user: &Pubkey,
+) -> Result<bool> {
+    if token_account.owner != &spl_token_2022::ID || token_account.data_is_empty() {
+        return Ok(false);
+    }
+    let data = token_account.try_borrow_data()?;
+    let token_account = StateWithExtensions::<Token2022Account>::unpack(&data)
+        .map_err(|_| ErrorCode::InvalidWalienTokenAccount)?;
+    require!(
+        token_account.base.mint == *mint
+            && token_account.base.owner == *user
+            && token_account.base.state != AccountState::Frozen,
+        ErrorCode::InvalidWalienTokenAccount
+    );
+    Ok(true)
+}
+
+/// Accounts paying claimed Walien out of the vault.
+pub struct WalienPayout<'info> {
+    /// Pays for missing ATAs.
+    pub payer: AccountInfo<'info>,
+    pub walien_mint: AccountInfo<'info>,
+    pub walien_decimals: u8,
+    pub vault: AccountInfo<'info>,
+    pub global_config: AccountInfo<'info>,
+    pub token_program_2022: AccountInfo<'info>,
+    pub associated_token_program: AccountInfo<'info>,
+    pub system_program: AccountInfo<'info>,
+    /// Forwarded to the Walien transfer hook, if any.
+    pub hook_accounts: Vec<AccountInfo<'info>>,
+}
+
+impl<'info> WalienPayout<'info> {
+    /// Sends `amount` to `user`'s ATA, creating it first unless it exists. The vault covers
+    /// any transfer fee so that the user receives the full amount.
+    pub fn pay(
+        &self,
+        user: AccountInfo<'info>,
+        user_walien_token_account: AccountInfo<'info>,
+        ata_exists: bool,
+        amount: u64,
+        config_signer: &[&[&[u8]]],
+    ) -> Result<()> {
+        if !ata_exists {
+            create(CpiContext::new(
+                self.associated_token_program.clone(),
+                Create {
+                    payer: self.payer.clone(),
+                    associated_token: user_walien_token_account.clone(),
+                    authority: user,
+                    mint: self.walien_mint.clone(),
+                    system_program: self.system_program.clone(),
+                    token_program: self.token_program_2022.clone(),
+                },
+            ))?;
+        }
+
+        let transfer_ctx = CpiContext::new_with_signer(
+            self.token_program_2022.clone(),
+            TransferChecked {
+                from: self.vault.clone(),
+                to: user_walien_token_account,
+                authority: self.global_config.clone(),
+                mint: self.walien_mint.clone(),
+            },
+            config_signer,
+        )
+        .with_remaining_accounts(self.hook_accounts.clone());
+        let gross_amount = amount_with_transfer_fee(&self.walien_mint, amount)?;
+        transfer_checked_with_hook(transfer_ctx, gross_amount, self.walien_decimals)
+    }
+}
+
+/// Forwards `usdc_amount` quote units of claim proceeds from the pay mint's vault to the admin.
+pub fn forward_proceeds<'info>(
+    usdc_mint: &InterfaceAccount<'info, SplMint>,
+    vault: AccountInfo<'info>,
+    admin_token_account: AccountInfo<'info>,
+    global_config: AccountInfo<'info>,
+    token_program: AccountInfo<'info>,
+    usdc_amount: u64,
+    config_signer: &[&[&[u8]]],
+) -> Result<()> {
+    let pay_amount = to_pay_amount(usdc_amount, usdc_mint.decimals, false)?;
+    if pay_amount == 0 {
+        return Ok(());
+    }
+    token_interface::transfer_checked(
+        CpiContext::new_with_signer(
+            token_program,
+            token_interface::TransferChecked {
+                from: vault,
+                to: admin_token_account,
+                authority: global_config,
+                mint: usdc_mint.to_account_info(),
+            },
+            config_signer,
+        ),
+        pay_amount,
+        usdc_mint.decimals,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::solana_program::program_pack::Pack;
+
+    fn config(vesting_duration_secs: i64) -> GlobalConfig {
+        // all-zero config: no cliff, no flags, nothing set
+        let mut cfg =
+            GlobalConfig::try_deserialize_unchecked(&mut &[0u8; GlobalConfig::SIZE][..]).unwrap();
+        cfg.claim_start_ts = 1_000;
+        cfg.vesting_duration_secs = vesting_duration_secs;
+        cfg
+    }
+
+    fn position(usdc_spent: u64, walien_allocation: u64) -> UserPosition {
+        UserPosition {
+            authority: Pubkey::new_unique(),
+            index: 7,
+            usdc_spent,
+            walien_allocation,
+            claimed_amount: 0,
+            last_buy_timestamp: 0,
+            pay_mint: Pubkey::default(),
+            receipt_mint: None,
+        }
+    }
+
+    fn summary(total_usdc_locked: u64, total_walien_alloc: u64) -> UserSummary {
+        UserSummary {
+            authority: Pubkey::new_unique(),
+            total_usdc_locked,
+            total_walien_alloc,
+            last_buy_timestamp: 0,
+            global_index_position: 0,
+            index_position: 0,
+        }
+    }
+
+    #[test]
+    fn test_vesting_claims_book_partially_then_fully() {
+        let cfg = config(100);
+        let mut position = position(300, 1_000);
+        let mut summary = summary(300, 1_000);
+
+        let claim = PositionClaim::compute(&cfg, &position, 1_025).unwrap();
+        assert_eq!(claim.walien_amount, 250);
+        assert_eq!(claim.usdc_amount, 75);
+        assert!(!claim.book(&mut position, &mut summary).unwrap());
+        assert_eq!(position.claimed_amount, 250);
+        assert_eq!(position.walien_allocation, 1_000);
+        assert_eq!(
+            (summary.total_walien_alloc, summary.total_usdc_locked),
+            (750, 225)
+        );
+
+        // nothing new vested in the same second
+        let claim = PositionClaim::compute(&cfg, &position, 1_025).unwrap();
+        assert_eq!((claim.walien_amount, claim.usdc_amount), (0, 0));
+        assert_eq!(claim.claimed_after, 250);
+
+        let claim = PositionClaim::compute(&cfg, &position, 5_000).unwrap();
+        assert_eq!((claim.walien_amount, claim.usdc_amount), (750, 225));
+        assert!(claim.book(&mut position, &mut summary).unwrap());
+        assert_eq!(position.walien_allocation, 0);
+        assert!(summary.is_empty());
+    }
+
+    #[test]
+    fn test_booking_more_than_the_summary_holds_fails() {
+        let cfg = config(0);
+        let mut position = position(300, 1_000);
+        let mut summary = summary(300, 999);
+        let claim = PositionClaim::compute(&cfg, &position, 1_000).unwrap();
+        assert_eq!(
+            claim.book(&mut position, &mut summary).unwrap_err(),
+            ErrorCode::UserSummaryUnderflow.into()
+        );
+    }
+
+    #[test]
+    fn test_walien_ata_checks() {
+        let mint = Pubkey::new_unique();
+        let user = Pubkey::new_unique();
+        let check = |mut data: Vec<u8>, program: Pubkey| {
+            let key = Pubkey::new_unique();
+            let mut lamports = 0;
+            let info = AccountInfo::new(
+                &key,
+                false,
+                true,
+                &mut lamports,
+                &mut data,
+                &program,
+                false,
+                0,
+            );
+            walien_ata_exists(&info, &mint, &user)
+        };
+        let ata = |mint, owner, state| {
+            let mut data = vec![0u8; Token2022Account::LEN];
+            Token2022Account {
+                mint,
+                owner,
+                state,
+                ..Default::default()
+            }
+            .pack_into_slice(&mut data);
+            data
+        };
+
+        assert!(!check(vec![], spl_token_2022::ID).unwrap());
+        assert!(!check(vec![], Pubkey::default()).unwrap());
+        assert!(check(
+            ata(mint, user, AccountState::Initialized),
+            spl_token_2022::ID
+        )
+        .unwrap());
+        for data in [
+            ata(mint, user, AccountState::Frozen),
+            ata(mint, Pubkey::new_unique(), AccountState::Initialized),
+            ata(Pubkey::new_unique(), user, AccountState::Initialized),
+        ] {
+            assert_eq!(
+                check(data, spl_token_2022::ID).unwrap_err(),
+                ErrorCode::InvalidWalienTokenAccount.into()
+            );
+        }
+    }
+}
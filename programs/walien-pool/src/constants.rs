@@ -1,7 +1,29 @@
+pub const REGISTRY_SEED: &[u8] = b"registry";
 pub const CONFIG_SEED: &[u8] = b"config";
 pub const VAULT_USDC_SEED: &[u8] = b"vault_usdc";
 pub const VAULT_WALIEN_SEED: &[u8] = b"vault_walien";
 pub const USER_SUMMARY_SEED: &[u8] = b"user_summary";
+pub const REFERRER_SEED: &[u8] = b"referrer";
+pub const LIQUIDITY_CURVE_SEED: &[u8] = b"liquidity_curve";
+pub const PAY_MINT_SEED: &[u8] = b"pay_mint";
+pub const RECEIPT_MINT_SEED: &[u8] = b"receipt_mint";
+
+// Bits of `GlobalConfig::pause_flags`, set with `set_pause_flags`
+pub const PAUSE_BUY: u8 = 1 << 0;
+pub const PAUSE_CLAIM: u8 = 1 << 1;
+pub const PAUSE_REFUND: u8 = 1 << 2;
+pub const PAUSE_ADMIN_WITHDRAW: u8 = 1 << 3;
+pub const PAUSE_TRANSFER: u8 = 1 << 4;
+pub const PAUSE_ALL: u8 =
+    PAUSE_BUY | PAUSE_CLAIM | PAUSE_REFUND | PAUSE_ADMIN_WITHDRAW | PAUSE_TRANSFER;
+
+// `possition_index` of a pool's first position, see `GlobalConfig::has_positions`
+pub const FIRST_POSITION_INDEX: u64 = 1;
+
+pub const MAX_POOLS: usize = 32;
+pub const MAX_LIQUIDITY_RANGES: usize = 8;
 
 pub const USDC_DECIMALS: u8 = 6;
 pub const WALIEN_DECIMALS: u8 = 9;
+
+pub const BPS_DENOMINATOR: u64 = 10_000;
@@ -72,6 +72,44 @@ pub enum ErrorCode {
     InsufficientAvailableForSwap,
     #[msg("USDC cap exceeded")]
     UsdcCapExceeded,
+    #[msg("USDC amount below minimum purchase")]
+    UsdcBelowMinimum,
+    #[msg("Invalid purchase limits")]
+    InvalidPurchaseLimits,
+    #[msg("Sale is active")]
+    SaleIsActive,
+    #[msg("Pool already has positions")]
+    PoolHasPositions,
+    #[msg("Signer is not the pending admin")]
+    NotPendingAdmin,
+    #[msg("No admin proposal pending")]
+    NoPendingAdmin,
+    #[msg("Signer is not the program's upgrade authority")]
+    NotUpgradeAuthority,
+    #[msg("Fee rate exceeds the maximum")]
+    FeeRateTooHigh,
+    #[msg("No fees to collect")]
+    NoFeesToCollect,
+    #[msg("Invalid fee schedule")]
+    InvalidFeeSchedule,
+    #[msg("Invalid referral reward rate")]
+    InvalidReferralRate,
+    #[msg("Buyer cannot refer themselves")]
+    SelfReferral,
+    #[msg("Sale failed to reach the soft cap")]
+    SaleFailed,
+    #[msg("Invalid liquidity curve")]
+    InvalidLiquidityCurve,
+    #[msg("Liquidity curve account does not match the pool")]
+    LiquidityCurveMismatch,
+    #[msg("Clear the liquidity curve with set_liquidity_curve first")]
+    LiquidityCurveSet,
+    #[msg("Mint is not accepted as payment")]
+    InvalidPayMint,
+    #[msg("Vault received less than the amount due")]
+    PayTransferShortfall,
+    #[msg("Walien mint has an unsupported Token-2022 extension")]
+    UnsupportedWalienExtension,
     #[msg("User summary underflow")]
     UserSummaryUnderflow,
     #[msg("Nothing to claim")]
@@ -91,6 +129,64 @@ pub enum ErrorCode {
     InvalidUsdcDecimals,
     #[msg("Invalid Walien decimals")]
     InvalidWalienDecimals,
+
+    #[msg("Pool registry is full")]
+    PoolRegistryFull,
+
+    #[msg("Sale has not started yet")]
+    SaleNotStarted,
+    #[msg("Sale has ended")]
+    SaleEnded,
+    #[msg("Claim has not started yet")]
+    ClaimNotStarted,
+    #[msg("Invalid sale or claim schedule")]
+    InvalidSchedule,
+    #[msg("Window has already started and can no longer be rescheduled")]
+    WindowAlreadyStarted,
+    #[msg("Invalid vesting schedule")]
+    InvalidVestingSchedule,
+    #[msg("Position is partially claimed")]
+    PositionPartiallyClaimed,
+    #[msg("Position account does not match its index or owner")]
+    InvalidPositionAccount,
+    #[msg("Position passed more than once")]
+    DuplicatePosition,
+    #[msg("Missing accounts for the crank range")]
+    CrankAccountsMissing,
+    #[msg("Position transfers are disabled")]
+    TransferIsNotActive,
+    #[msg("Position already belongs to this wallet")]
+    SameOwner,
+    #[msg("Receipt accounts are required")]
+    ReceiptAccountsMissing,
+    #[msg("Token account does not hold the position's receipt")]
+    InvalidReceipt,
+    #[msg("Position is held through its receipt NFT")]
+    PositionHasReceipt,
+    #[msg("Buys are paused")]
+    BuyPaused,
+    #[msg("Claims are paused")]
+    ClaimPaused,
+    #[msg("Refunds are paused")]
+    RefundPaused,
+    #[msg("Admin withdrawals are paused")]
+    AdminWithdrawPaused,
+    #[msg("Position transfers are paused")]
+    TransferPaused,
+    #[msg("Unknown pause flag")]
+    InvalidPauseFlags,
+    #[msg("Wallet contribution cap exceeded")]
+    WalletCapExceeded,
+    #[msg("Wallet contribution below minimum")]
+    BelowMinContribution,
+    #[msg("Invalid wallet contribution bounds")]
+    InvalidWalletCaps,
+    #[msg("Allowlist merkle root is not set")]
+    AllowlistRootNotSet,
+    #[msg("Invalid allowlist merkle proof")]
+    InvalidMerkleProof,
+    #[msg("Allowlist cap exceeded")]
+    AllowlistCapExceeded,
 }
 
 impl From<TryFromIntError> for ErrorCode {
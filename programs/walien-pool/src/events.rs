@@ -1,20 +1,158 @@
+use crate::state::LiquidityRange;
 use anchor_lang::prelude::*;
 
 #[event]
 pub struct BuyEvent {
+    pub pool_id: u64,
     pub user: Pubkey,
     pub user_position: Pubkey,
     pub position_index: u64,
+    /// Total charged in quote units (USDC), fee included.
     pub usdc_amount: u64,
+    pub fee_amount: u64,
+    pub pay_mint: Pubkey,
+    /// Credited to the vault in `pay_mint` units, after any transfer fee.
+    pub pay_amount: u64,
     pub walien_amount: u64,
     pub price_after: u128,
 }
 
 #[event]
 pub struct ClaimEvent {
+    pub pool_id: u64,
     pub caller: Pubkey,
     pub user: Pubkey,
     pub user_position: Pubkey,
     pub position_index: u64,
+    /// Released by this claim.
     pub walien_amount: u64,
+    /// USDC forwarded to the admin by this claim.
+    pub usdc_amount: u64,
+    /// Total released from the position so far.
+    pub claimed_amount: u64,
+    pub walien_allocation: u64,
+}
+
+#[event]
+pub struct PoolParamsUpdatedEvent {
+    pub pool_id: u64,
+    pub old_initial_sqrt_price_x64: u128,
+    pub new_initial_sqrt_price_x64: u128,
+    pub old_tick_upper: i32,
+    pub new_tick_upper: i32,
+    pub old_available_for_swap_in_usdc: u64,
+    pub new_available_for_swap_in_usdc: u64,
+    pub old_liquidity: u128,
+    pub new_liquidity: u128,
+    pub old_fee_bps: u16,
+    pub new_fee_bps: u16,
+}
+
+#[event]
+pub struct AdminProposedEvent {
+    pub pool_id: u64,
+    pub admin: Pubkey,
+    pub pending_admin: Pubkey,
+}
+
+#[event]
+pub struct AdminAcceptedEvent {
+    pub pool_id: u64,
+    pub old_admin: Pubkey,
+    pub new_admin: Pubkey,
+}
+
+#[event]
+pub struct AdminProposalCancelledEvent {
+    pub pool_id: u64,
+    pub admin: Pubkey,
+    pub pending_admin: Pubkey,
+}
+
+#[event]
+pub struct RegistryAdminProposedEvent {
+    pub admin: Pubkey,
+    pub pending_admin: Pubkey,
+}
+
+#[event]
+pub struct RegistryAdminAcceptedEvent {
+    pub old_admin: Pubkey,
+    pub new_admin: Pubkey,
+}
+
+#[event]
+pub struct FeesCollectedEvent {
+    pub pool_id: u64,
+    pub fee_recipient: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct ReferralEvent {
+    pub pool_id: u64,
+    pub referrer: Pubkey,
+    pub user: Pubkey,
+    pub user_position: Pubkey,
+    pub usdc_amount: u64,
+    pub usdc_reward: u64,
+    pub walien_reward: u64,
+}
+
+#[event]
+pub struct ReferralRewardsClaimedEvent {
+    pub pool_id: u64,
+    pub referrer: Pubkey,
+    pub usdc_amount: u64,
+    pub walien_amount: u64,
+}
+
+#[event]
+pub struct LiquidityCurveUpdatedEvent {
+    pub pool_id: u64,
+    pub ranges: Vec<LiquidityRange>,
+}
+
+#[event]
+pub struct PayMintRegisteredEvent {
+    pub pool_id: u64,
+    pub mint: Pubkey,
+    pub decimals: u8,
+}
+
+#[event]
+pub struct PositionsMergedEvent {
+    pub pool_id: u64,
+    pub user: Pubkey,
+    pub user_position: Pubkey,
+    pub position_index: u64,
+    pub merged_indices: Vec<u64>,
+    pub usdc_spent: u64,
+    pub walien_allocation: u64,
+}
+
+#[event]
+pub struct PositionTransferredEvent {
+    pub pool_id: u64,
+    pub user_position: Pubkey,
+    pub position_index: u64,
+    pub from: Pubkey,
+    pub to: Pubkey,
+    /// Unclaimed allocation and locked USDC moved between the owners' summaries.
+    pub walien_amount: u64,
+    pub usdc_amount: u64,
+}
+
+#[event]
+pub struct ClaimsCrankedEvent {
+    pub pool_id: u64,
+    pub caller: Pubkey,
+    pub start_index: u64,
+    pub processed: u8,
+    pub skipped: u8,
+    /// Positions fully claimed and closed, the ones the rewards are paid for.
+    pub closed: u8,
+    pub reward_lamports: u64,
+    pub reward_walien: u64,
 }
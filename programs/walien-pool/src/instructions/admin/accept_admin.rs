@@ -0,0 +1,38 @@
+use crate::{
+    constants::CONFIG_SEED, errors::ErrorCode, events::AdminAcceptedEvent, state::GlobalConfig,
+};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct AcceptAdmin<'info> {
+    #[account(
+        mut,
+        seeds = [
+            CONFIG_SEED,
+            global_config_account.pool_id.to_le_bytes().as_ref()
+        ],
+        bump = global_config_account.bump
+    )]
+    pub global_config_account: Account<'info, GlobalConfig>,
+    #[account(
+        constraint = global_config_account.pending_admin == Some(new_admin.key())
+            @ ErrorCode::NotPendingAdmin
+    )]
+    pub new_admin: Signer<'info>,
+}
+
+impl<'info> AcceptAdmin<'info> {
+    pub fn apply(ctx: &mut Context<AcceptAdmin>) -> Result<()> {
+        let cfg = &mut ctx.accounts.global_config_account;
+        let old_admin = cfg.admin;
+        cfg.admin = ctx.accounts.new_admin.key();
+        cfg.pending_admin = None;
+
+        emit!(AdminAcceptedEvent {
+            pool_id: cfg.pool_id,
+            old_admin,
+            new_admin: cfg.admin,
+        });
+        Ok(())
+    }
+}
@@ -0,0 +1,34 @@
+use crate::{
+    constants::REGISTRY_SEED, errors::ErrorCode, events::RegistryAdminAcceptedEvent,
+    state::PoolRegistry,
+};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct AcceptRegistryAdmin<'info> {
+    #[account(
+        mut,
+        seeds = [REGISTRY_SEED],
+        bump = registry.bump
+    )]
+    pub registry: Account<'info, PoolRegistry>,
+    #[account(
+        constraint = registry.pending_admin == Some(new_admin.key()) @ ErrorCode::NotPendingAdmin
+    )]
+    pub new_admin: Signer<'info>,
+}
+
+impl<'info> AcceptRegistryAdmin<'info> {
+    pub fn apply(ctx: &mut Context<AcceptRegistryAdmin>) -> Result<()> {
+        let registry = &mut ctx.accounts.registry;
+        let old_admin = registry.admin;
+        registry.admin = ctx.accounts.new_admin.key();
+        registry.pending_admin = None;
+
+        emit!(RegistryAdminAcceptedEvent {
+            old_admin,
+            new_admin: registry.admin,
+        });
+        Ok(())
+    }
+}
@@ -0,0 +1,36 @@
+use crate::{
+    constants::CONFIG_SEED, errors::ErrorCode, events::AdminProposalCancelledEvent,
+    state::GlobalConfig,
+};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct CancelAdminProposal<'info> {
+    #[account(
+        mut,
+        seeds = [
+            CONFIG_SEED,
+            global_config_account.pool_id.to_le_bytes().as_ref()
+        ],
+        bump = global_config_account.bump
+    )]
+    pub global_config_account: Account<'info, GlobalConfig>,
+    #[account(
+        mut,
+        constraint = global_config_account.admin == admin.key())]
+    pub admin: Signer<'info>,
+}
+
+impl<'info> CancelAdminProposal<'info> {
+    pub fn apply(ctx: &mut Context<CancelAdminProposal>) -> Result<()> {
+        let cfg = &mut ctx.accounts.global_config_account;
+        let pending_admin = cfg.pending_admin.take().ok_or(ErrorCode::NoPendingAdmin)?;
+
+        emit!(AdminProposalCancelledEvent {
+            pool_id: cfg.pool_id,
+            admin: ctx.accounts.admin.key(),
+            pending_admin,
+        });
+        Ok(())
+    }
+}
@@ -0,0 +1,109 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
+
+use crate::{
+    constants::{CONFIG_SEED, PAUSE_ADMIN_WITHDRAW, PAY_MINT_SEED, VAULT_USDC_SEED},
+    errors::ErrorCode,
+    events::FeesCollectedEvent,
+    state::{GlobalConfig, PayMint},
+};
+
+#[derive(Accounts)]
+pub struct CollectFees<'info> {
+    #[account(
+        mut,
+        seeds = [
+            CONFIG_SEED,
+            global_config_account.pool_id.to_le_bytes().as_ref()
+        ],
+        bump = global_config_account.bump
+    )]
+    pub global_config_account: Account<'info, GlobalConfig>,
+    #[account(
+        mut,
+        constraint = global_config_account.admin == admin.key())]
+    pub admin: Signer<'info>,
+    /// `usdc_mint` or a registered pay mint (then `pay_mint` is required).
+    #[account(
+        constraint = global_config_account.usdc_mint == usdc_mint.key()
+            || pay_mint.is_some() @ ErrorCode::InvalidPayMint
+    )]
+    pub usdc_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        seeds = [
+            PAY_MINT_SEED,
+            global_config_account.key().as_ref(),
+            usdc_mint.key().as_ref()
+        ],
+        bump = pay_mint.bump
+    )]
+    pub pay_mint: Option<Account<'info, PayMint>>,
+    #[account(
+        mut,
+        seeds = [
+            VAULT_USDC_SEED,
+            global_config_account.key().as_ref(),
+            global_config_account.pay_vault_seed(&usdc_mint.key())
+        ],
+        bump,
+        token::mint = usdc_mint,
+        token::authority = global_config_account,
+        token::token_program = token_program,
+    )]
+    pub program_usdc_token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        token::mint = usdc_mint,
+        token::authority = global_config_account.fee_recipient,
+        token::token_program = token_program,
+    )]
+    pub fee_recipient_usdc_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+impl<'info> CollectFees<'info> {
+    pub fn apply(ctx: &mut Context<CollectFees>) -> Result<()> {
+        ctx.accounts
+            .global_config_account
+            .require_not_paused(PAUSE_ADMIN_WITHDRAW)?;
+        let amount = match ctx.accounts.pay_mint.as_ref() {
+            Some(pay_mint) => pay_mint.accrued_fees,
+            None => ctx.accounts.global_config_account.accrued_fees,
+        };
+        require!(amount > 0, ErrorCode::NoFeesToCollect);
+
+        let pool_id_bytes = ctx.accounts.global_config_account.pool_id.to_le_bytes();
+        let seeds = &[
+            CONFIG_SEED,
+            pool_id_bytes.as_ref(),
+            &[ctx.accounts.global_config_account.bump],
+        ];
+        let signer = &[&seeds[..]];
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.program_usdc_token_account.to_account_info(),
+            to: ctx
+                .accounts
+                .fee_recipient_usdc_token_account
+                .to_account_info(),
+            authority: ctx.accounts.global_config_account.to_account_info(),
+            mint: ctx.accounts.usdc_mint.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let transfer_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+        token_interface::transfer_checked(transfer_ctx, amount, ctx.accounts.usdc_mint.decimals)?;
+
+        match ctx.accounts.pay_mint.as_mut() {
+            Some(pay_mint) => pay_mint.accrued_fees = 0,
+            None => ctx.accounts.global_config_account.accrued_fees = 0,
+        }
+
+        emit!(FeesCollectedEvent {
+            pool_id: ctx.accounts.global_config_account.pool_id,
+            fee_recipient: ctx.accounts.global_config_account.fee_recipient,
+            mint: ctx.accounts.usdc_mint.key(),
+            amount,
+        });
+        Ok(())
+    }
+}
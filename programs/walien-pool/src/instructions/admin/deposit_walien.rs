@@ -1,5 +1,5 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token_2022::{Token2022, TransferChecked, transfer_checked};
+use anchor_spl::token_2022::{Token2022, TransferChecked};
 use anchor_spl::token_interface::{
     Mint as Token2022Mint, TokenAccount as Token2022TokenAccount,
 };
@@ -7,6 +7,7 @@ use anchor_spl::token_interface::{
 use crate::{
     constants::{CONFIG_SEED, VAULT_WALIEN_SEED},
     state::GlobalConfig,
+    token_extensions::transfer_checked_with_hook,
 };
 
 #[derive(Accounts)]
@@ -14,9 +15,10 @@ pub struct DepositWalien<'info> {
     #[account(
         mut,
         seeds = [
-            CONFIG_SEED
+            CONFIG_SEED,
+            global_config_account.pool_id.to_le_bytes().as_ref()
         ],
-        bump
+        bump = global_config_account.bump
     )]
     pub global_config_account: Account<'info, GlobalConfig>,
     #[account(
@@ -36,7 +38,7 @@ pub struct DepositWalien<'info> {
     pub admin_walien_token_account: InterfaceAccount<'info, Token2022TokenAccount>,
     #[account(
         mut,
-        seeds = [VAULT_WALIEN_SEED],
+        seeds = [VAULT_WALIEN_SEED, global_config_account.key().as_ref()],
         bump,
         token::mint = walien_mint,
         token::authority = global_config_account,
@@ -47,7 +49,10 @@ pub struct DepositWalien<'info> {
 }
 
 impl<'info> DepositWalien<'info> {
-    pub fn apply(ctx: &mut Context<DepositWalien>, amount: u64) -> Result<()> {
+    pub fn apply(
+        ctx: &mut Context<'_, '_, '_, 'info, DepositWalien<'info>>,
+        amount: u64,
+    ) -> Result<()> {
         {
             let cpi_accounts = TransferChecked {
                 from: ctx.accounts.admin_walien_token_account.to_account_info(),
@@ -56,9 +61,10 @@ impl<'info> DepositWalien<'info> {
                 mint: ctx.accounts.walien_mint.to_account_info(),
             };
             let cpi_program = ctx.accounts.token_program.to_account_info();
-            let transfer_ctx = CpiContext::new(cpi_program, cpi_accounts);
+            let transfer_ctx = CpiContext::new(cpi_program, cpi_accounts)
+                .with_remaining_accounts(ctx.remaining_accounts.to_vec());
 
-            transfer_checked(transfer_ctx, amount, ctx.accounts.walien_mint.decimals)?;
+            transfer_checked_with_hook(transfer_ctx, amount, ctx.accounts.walien_mint.decimals)?;
         }
 
         Ok(())
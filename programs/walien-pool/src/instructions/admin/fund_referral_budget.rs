@@ -0,0 +1,84 @@
+use crate::{
+    constants::{CONFIG_SEED, VAULT_WALIEN_SEED},
+    errors::ErrorCode,
+    state::GlobalConfig,
+    token_extensions::transfer_checked_with_hook,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token_2022::{Token2022, TransferChecked};
+use anchor_spl::token_interface::{Mint as Token2022Mint, TokenAccount as Token2022TokenAccount};
+
+#[derive(Accounts)]
+pub struct FundReferralBudget<'info> {
+    #[account(
+        mut,
+        seeds = [
+            CONFIG_SEED,
+            global_config_account.pool_id.to_le_bytes().as_ref()
+        ],
+        bump = global_config_account.bump
+    )]
+    pub global_config_account: Account<'info, GlobalConfig>,
+    #[account(
+        mut,
+        constraint = global_config_account.admin == admin.key())]
+    pub admin: Signer<'info>,
+    #[account(
+        constraint = global_config_account.walien_mint == Some(walien_mint.key())
+    )]
+    pub walien_mint: InterfaceAccount<'info, Token2022Mint>,
+    #[account(
+        mut,
+        associated_token::mint = walien_mint,
+        associated_token::authority = admin,
+        associated_token::token_program = token_program,
+    )]
+    pub admin_walien_token_account: InterfaceAccount<'info, Token2022TokenAccount>,
+    #[account(
+        mut,
+        seeds = [VAULT_WALIEN_SEED, global_config_account.key().as_ref()],
+        bump,
+        token::mint = walien_mint,
+        token::authority = global_config_account,
+        token::token_program = token_program,
+    )]
+    pub program_walien_token_account: InterfaceAccount<'info, Token2022TokenAccount>,
+    pub token_program: Program<'info, Token2022>,
+}
+
+impl<'info> FundReferralBudget<'info> {
+    /// Moves `amount` wALIEN into the vault and adds what arrived to `referral_walien_budget`,
+    /// the only source of Walien referral rewards. Remaining accounts go to the transfer hook.
+    pub fn apply(
+        ctx: &mut Context<'_, '_, '_, 'info, FundReferralBudget<'info>>,
+        amount: u64,
+    ) -> Result<()> {
+        let balance_before = ctx.accounts.program_walien_token_account.amount;
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.admin_walien_token_account.to_account_info(),
+            to: ctx.accounts.program_walien_token_account.to_account_info(),
+            authority: ctx.accounts.admin.to_account_info(),
+            mint: ctx.accounts.walien_mint.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let transfer_ctx = CpiContext::new(cpi_program, cpi_accounts)
+            .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+        transfer_checked_with_hook(transfer_ctx, amount, ctx.accounts.walien_mint.decimals)?;
+
+        // with a transfer fee the vault receives less than amount
+        ctx.accounts.program_walien_token_account.reload()?;
+        let received = ctx
+            .accounts
+            .program_walien_token_account
+            .amount
+            .checked_sub(balance_before)
+            .ok_or(ErrorCode::AmountCalcOverflow)?;
+        ctx.accounts.global_config_account.referral_walien_budget = ctx
+            .accounts
+            .global_config_account
+            .referral_walien_budget
+            .checked_add(received)
+            .ok_or(ErrorCode::AmountCalcOverflow)?;
+        Ok(())
+    }
+}
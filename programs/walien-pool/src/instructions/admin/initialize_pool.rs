@@ -0,0 +1,120 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+use crate::{
+    constants::{
+        CONFIG_SEED, FIRST_POSITION_INDEX, MAX_POOLS, REGISTRY_SEED, USDC_DECIMALS, VAULT_USDC_SEED,
+    },
+    errors::ErrorCode,
+    state::{GlobalConfig, PoolRegistry},
+    utils::validate_pool_params,
+};
+
+#[derive(Accounts)]
+#[instruction(pool_id: u64)]
+pub struct InitializePool<'info> {
+    #[account(
+        mut,
+        constraint = registry.admin == admin.key())]
+    pub admin: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [REGISTRY_SEED],
+        bump = registry.bump
+    )]
+    pub registry: Account<'info, PoolRegistry>,
+    #[account(
+        init,
+        space = GlobalConfig::SIZE,
+        payer = admin,
+        seeds = [
+            CONFIG_SEED,
+            pool_id.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub global_config_account: Account<'info, GlobalConfig>,
+    #[account()]
+    pub usdc_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        init,
+        payer = admin,
+        seeds = [VAULT_USDC_SEED, global_config_account.key().as_ref()],
+        bump,
+        token::mint = usdc_mint,
+        token::authority = global_config_account,
+        token::token_program = token_program,
+    )]
+    pub program_usdc_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+impl<'info> InitializePool<'info> {
+    #[allow(clippy::too_many_arguments)]
+    pub fn apply(
+        ctx: &mut Context<InitializePool>,
+        pool_id: u64,
+        initial_sqrt_price_x64: u128,
+        tick_upper: i32,
+        available_for_swap_in_usdc: u64,
+        liquidity: u128,
+        fee_bps: u16,
+        min_usdc_per_buy: u64,
+        max_usdc_per_buy: u64,
+        sale_start_ts: i64,
+        sale_end_ts: i64,
+        claim_start_ts: i64,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.usdc_mint.decimals == USDC_DECIMALS,
+            ErrorCode::InvalidUsdcDecimals
+        );
+
+        validate_pool_params(initial_sqrt_price_x64, tick_upper, liquidity, fee_bps)?;
+        require!(
+            max_usdc_per_buy == 0 || min_usdc_per_buy <= max_usdc_per_buy,
+            ErrorCode::InvalidPurchaseLimits
+        );
+        GlobalConfig::validate_schedule(sale_start_ts, sale_end_ts, claim_start_ts)?;
+        require!(
+            ctx.accounts.registry.pool_ids.len() < MAX_POOLS,
+            ErrorCode::PoolRegistryFull
+        );
+        ctx.accounts.registry.pool_ids.push(pool_id);
+
+        ctx.accounts.global_config_account.admin = ctx.accounts.admin.key();
+        ctx.accounts.global_config_account.pending_admin = None;
+        ctx.accounts.global_config_account.pool_id = pool_id;
+        ctx.accounts.global_config_account.usdc_mint = ctx.accounts.usdc_mint.key();
+        ctx.accounts.global_config_account.walien_mint = None;
+        ctx.accounts.global_config_account.is_sale_active = true;
+        ctx.accounts.global_config_account.is_claim_active = true;
+        ctx.accounts.global_config_account.is_transfer_active = true;
+        ctx.accounts.global_config_account.sale_start_ts = sale_start_ts;
+        ctx.accounts.global_config_account.sale_end_ts = sale_end_ts;
+        ctx.accounts.global_config_account.claim_start_ts = claim_start_ts;
+        ctx.accounts.global_config_account.bump = ctx.bumps.global_config_account;
+        ctx.accounts.global_config_account.fee_bps = fee_bps;
+        ctx.accounts.global_config_account.accrued_fees = 0;
+        ctx.accounts.global_config_account.fee_recipient = ctx.accounts.admin.key();
+        ctx.accounts.global_config_account.min_usdc_per_buy = min_usdc_per_buy;
+        ctx.accounts.global_config_account.max_usdc_per_buy = max_usdc_per_buy;
+        ctx.accounts.global_config_account.initial_sqrt_price_x64 = initial_sqrt_price_x64;
+        ctx.accounts.global_config_account.floor_sqrt_price_x64 = initial_sqrt_price_x64;
+        ctx.accounts.global_config_account.tick_upper = tick_upper;
+        ctx.accounts.global_config_account.has_liquidity_curve = false;
+        ctx.accounts
+            .global_config_account
+            .available_for_swap_in_usdc = available_for_swap_in_usdc;
+        ctx.accounts.global_config_account.possition_index = FIRST_POSITION_INDEX;
+        ctx.accounts.global_config_account.liqudity = liquidity;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,53 @@
+use crate::{
+    constants::REGISTRY_SEED, errors::ErrorCode, program::WalienPool, state::PoolRegistry,
+};
+use anchor_lang::prelude::*;
+
+/// Only the program's upgrade authority can create the registry, so nobody can front-run the
+/// deploy and take over pool creation.
+#[derive(Accounts)]
+pub struct InitializeRegistry<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()))]
+    pub program: Program<'info, WalienPool>,
+    #[account(
+        constraint = program_data.upgrade_authority_address == Some(admin.key())
+            @ ErrorCode::NotUpgradeAuthority
+    )]
+    pub program_data: Account<'info, ProgramData>,
+    #[account(
+        init,
+        space = PoolRegistry::SIZE,
+        payer = admin,
+        seeds = [REGISTRY_SEED],
+        bump
+    )]
+    pub registry: Account<'info, PoolRegistry>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> InitializeRegistry<'info> {
+    pub fn apply(ctx: &mut Context<InitializeRegistry>) -> Result<()> {
+        ctx.accounts.registry.admin = ctx.accounts.admin.key();
+        ctx.accounts.registry.pending_admin = None;
+        ctx.accounts.registry.pool_ids = Vec::new();
+        ctx.accounts.registry.bump = ctx.bumps.registry;
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct ListPools<'info> {
+    #[account(
+        seeds = [REGISTRY_SEED],
+        bump = registry.bump
+    )]
+    pub registry: Account<'info, PoolRegistry>,
+}
+
+impl<'info> ListPools<'info> {
+    pub fn apply(ctx: &Context<ListPools>) -> Result<Vec<u64>> {
+        Ok(ctx.accounts.registry.pool_ids.clone())
+    }
+}
@@ -1,17 +1,65 @@
+pub mod accept_admin;
+pub mod accept_registry_admin;
+pub mod cancel_admin_proposal;
+pub mod collect_fees;
 pub mod deposit_walien;
-pub mod initialize;
+pub mod fund_referral_budget;
+pub mod initialize_pool;
+pub mod initialize_registry;
+pub mod propose_admin;
+pub mod propose_registry_admin;
+pub mod register_pay_mint;
+pub mod register_referrer;
 pub mod rollback_position;
+pub mod set_allowlist_phase;
 pub mod set_claim_activity;
+pub mod set_crank_reward;
+pub mod set_fee_recipient;
+pub mod set_fee_schedule;
+pub mod set_liquidity_curve;
+pub mod set_merkle_root;
+pub mod set_pause_flags;
+pub mod set_position_receipts;
+pub mod set_purchase_limits;
 pub mod set_sale_activity;
+pub mod set_schedule;
+pub mod set_soft_cap;
+pub mod set_transfer_activity;
+pub mod set_vesting;
 pub mod set_walien;
-pub mod transfer_admin_authority;
+pub mod set_wallet_caps;
+pub mod update_pool_params;
 pub mod withdraw_walien;
 
+pub use accept_admin::*;
+pub use accept_registry_admin::*;
+pub use cancel_admin_proposal::*;
+pub use collect_fees::*;
 pub use deposit_walien::*;
-pub use initialize::*;
+pub use fund_referral_budget::*;
+pub use initialize_pool::*;
+pub use initialize_registry::*;
+pub use propose_admin::*;
+pub use propose_registry_admin::*;
+pub use register_pay_mint::*;
+pub use register_referrer::*;
 pub use rollback_position::*;
+pub use set_allowlist_phase::*;
 pub use set_claim_activity::*;
+pub use set_crank_reward::*;
+pub use set_fee_recipient::*;
+pub use set_fee_schedule::*;
+pub use set_liquidity_curve::*;
+pub use set_merkle_root::*;
+pub use set_pause_flags::*;
+pub use set_position_receipts::*;
+pub use set_purchase_limits::*;
 pub use set_sale_activity::*;
+pub use set_schedule::*;
+pub use set_soft_cap::*;
+pub use set_transfer_activity::*;
+pub use set_vesting::*;
 pub use set_walien::*;
-pub use transfer_admin_authority::*;
+pub use set_wallet_caps::*;
+pub use update_pool_params::*;
 pub use withdraw_walien::*;
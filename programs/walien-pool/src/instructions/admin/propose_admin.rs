@@ -0,0 +1,35 @@
+use crate::{constants::CONFIG_SEED, events::AdminProposedEvent, state::GlobalConfig};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct ProposeAdmin<'info> {
+    #[account(
+        mut,
+        seeds = [
+            CONFIG_SEED,
+            global_config_account.pool_id.to_le_bytes().as_ref()
+        ],
+        bump = global_config_account.bump
+    )]
+    pub global_config_account: Account<'info, GlobalConfig>,
+    #[account(
+        mut,
+        constraint = global_config_account.admin == admin.key())]
+    pub admin: Signer<'info>,
+    /// CHECK: only recorded here, it has to sign `accept_admin` to take over.
+    pub new_admin_authority: UncheckedAccount<'info>,
+}
+
+impl<'info> ProposeAdmin<'info> {
+    pub fn apply(ctx: &mut Context<ProposeAdmin>) -> Result<()> {
+        let pending_admin = ctx.accounts.new_admin_authority.key();
+        ctx.accounts.global_config_account.pending_admin = Some(pending_admin);
+
+        emit!(AdminProposedEvent {
+            pool_id: ctx.accounts.global_config_account.pool_id,
+            admin: ctx.accounts.admin.key(),
+            pending_admin,
+        });
+        Ok(())
+    }
+}
@@ -0,0 +1,31 @@
+use crate::{constants::REGISTRY_SEED, events::RegistryAdminProposedEvent, state::PoolRegistry};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct ProposeRegistryAdmin<'info> {
+    #[account(
+        mut,
+        seeds = [REGISTRY_SEED],
+        bump = registry.bump
+    )]
+    pub registry: Account<'info, PoolRegistry>,
+    #[account(
+        mut,
+        constraint = registry.admin == admin.key())]
+    pub admin: Signer<'info>,
+    /// CHECK: only recorded here, it has to sign `accept_registry_admin` to take over.
+    pub new_admin_authority: UncheckedAccount<'info>,
+}
+
+impl<'info> ProposeRegistryAdmin<'info> {
+    pub fn apply(ctx: &mut Context<ProposeRegistryAdmin>) -> Result<()> {
+        let pending_admin = ctx.accounts.new_admin_authority.key();
+        ctx.accounts.registry.pending_admin = Some(pending_admin);
+
+        emit!(RegistryAdminProposedEvent {
+            admin: ctx.accounts.admin.key(),
+            pending_admin,
+        });
+        Ok(())
+    }
+}
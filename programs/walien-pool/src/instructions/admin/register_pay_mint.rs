@@ -0,0 +1,75 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{
+    constants::{CONFIG_SEED, PAY_MINT_SEED, VAULT_USDC_SEED},
+    errors::ErrorCode,
+    events::PayMintRegisteredEvent,
+    state::{GlobalConfig, PayMint},
+};
+
+#[derive(Accounts)]
+pub struct RegisterPayMint<'info> {
+    #[account(
+        seeds = [
+            CONFIG_SEED,
+            global_config_account.pool_id.to_le_bytes().as_ref()
+        ],
+        bump = global_config_account.bump
+    )]
+    pub global_config_account: Account<'info, GlobalConfig>,
+    #[account(
+        mut,
+        constraint = global_config_account.admin == admin.key())]
+    pub admin: Signer<'info>,
+    #[account(
+        constraint = global_config_account.usdc_mint != mint.key() @ ErrorCode::InvalidPayMint
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        init,
+        space = PayMint::SIZE,
+        payer = admin,
+        seeds = [
+            PAY_MINT_SEED,
+            global_config_account.key().as_ref(),
+            mint.key().as_ref()
+        ],
+        bump
+    )]
+    pub pay_mint: Account<'info, PayMint>,
+    #[account(
+        init,
+        payer = admin,
+        seeds = [
+            VAULT_USDC_SEED,
+            global_config_account.key().as_ref(),
+            mint.key().as_ref()
+        ],
+        bump,
+        token::mint = mint,
+        token::authority = global_config_account,
+        token::token_program = token_program,
+    )]
+    pub program_pay_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> RegisterPayMint<'info> {
+    pub fn apply(ctx: &mut Context<RegisterPayMint>) -> Result<()> {
+        let pay_mint = &mut ctx.accounts.pay_mint;
+        pay_mint.global_config = ctx.accounts.global_config_account.key();
+        pay_mint.mint = ctx.accounts.mint.key();
+        pay_mint.decimals = ctx.accounts.mint.decimals;
+        pay_mint.accrued_fees = 0;
+        pay_mint.bump = ctx.bumps.pay_mint;
+
+        emit!(PayMintRegisteredEvent {
+            pool_id: ctx.accounts.global_config_account.pool_id,
+            mint: pay_mint.mint,
+            decimals: pay_mint.decimals,
+        });
+        Ok(())
+    }
+}
@@ -0,0 +1,101 @@
+use crate::{
+    constants::{BPS_DENOMINATOR, CONFIG_SEED, REFERRER_SEED},
+    errors::ErrorCode,
+    state::{GlobalConfig, ReferralRewardKind, Referrer},
+};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct RegisterReferrer<'info> {
+    #[account(
+        seeds = [
+            CONFIG_SEED,
+            global_config_account.pool_id.to_le_bytes().as_ref()
+        ],
+        bump = global_config_account.bump
+    )]
+    pub global_config_account: Account<'info, GlobalConfig>,
+    #[account(
+        mut,
+        constraint = global_config_account.admin == admin.key())]
+    pub admin: Signer<'info>,
+    /// CHECK: referrer wallet, it signs `claim_referral_rewards`.
+    pub referrer_authority: UncheckedAccount<'info>,
+    #[account(
+        init,
+        space = Referrer::SIZE,
+        payer = admin,
+        seeds = [
+            REFERRER_SEED,
+            global_config_account.key().as_ref(),
+            referrer_authority.key().as_ref()
+        ],
+        bump
+    )]
+    pub referrer: Account<'info, Referrer>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> RegisterReferrer<'info> {
+    pub fn apply(
+        ctx: &mut Context<RegisterReferrer>,
+        reward_kind: ReferralRewardKind,
+        reward_rate_bps: u16,
+    ) -> Result<()> {
+        require!(
+            reward_rate_bps as u64 <= BPS_DENOMINATOR,
+            ErrorCode::InvalidReferralRate
+        );
+
+        let referrer = &mut ctx.accounts.referrer;
+        referrer.authority = ctx.accounts.referrer_authority.key();
+        referrer.global_config = ctx.accounts.global_config_account.key();
+        referrer.reward_kind = reward_kind;
+        referrer.reward_rate_bps = reward_rate_bps;
+        referrer.bump = ctx.bumps.referrer;
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct SetReferrerReward<'info> {
+    #[account(
+        seeds = [
+            CONFIG_SEED,
+            global_config_account.pool_id.to_le_bytes().as_ref()
+        ],
+        bump = global_config_account.bump
+    )]
+    pub global_config_account: Account<'info, GlobalConfig>,
+    #[account(
+        constraint = global_config_account.admin == admin.key())]
+    pub admin: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [
+            REFERRER_SEED,
+            global_config_account.key().as_ref(),
+            referrer.authority.as_ref()
+        ],
+        bump = referrer.bump
+    )]
+    pub referrer: Account<'info, Referrer>,
+}
+
+impl<'info> SetReferrerReward<'info> {
+    /// Only affects future buys, already accrued rewards are kept.
+    pub fn apply(
+        ctx: &mut Context<SetReferrerReward>,
+        reward_kind: ReferralRewardKind,
+        reward_rate_bps: u16,
+    ) -> Result<()> {
+        require!(
+            reward_rate_bps as u64 <= BPS_DENOMINATOR,
+            ErrorCode::InvalidReferralRate
+        );
+
+        ctx.accounts.referrer.reward_kind = reward_kind;
+        ctx.accounts.referrer.reward_rate_bps = reward_rate_bps;
+        Ok(())
+    }
+}
@@ -1,13 +1,16 @@
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token::{self, Mint, Token, TokenAccount},
+    token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked},
 };
 
 use crate::{
-    constants::{CONFIG_SEED, USER_SUMMARY_SEED, VAULT_USDC_SEED},
+    constants::{
+        CONFIG_SEED, LIQUIDITY_CURVE_SEED, PAUSE_REFUND, USER_SUMMARY_SEED, VAULT_USDC_SEED,
+    },
     errors::ErrorCode,
-    state::{GlobalConfig, UserPosition, UserSummary},
+    state::{GlobalConfig, LiquidityCurve, UserPosition, UserSummary},
+    utils::{calculate_refund_price_from_config, to_pay_amount},
 };
 
 #[derive(Accounts)]
@@ -16,9 +19,10 @@ pub struct RollbackPosition<'info> {
     #[account(
         mut,
         seeds = [
-            CONFIG_SEED
+            CONFIG_SEED,
+            global_config_account.pool_id.to_le_bytes().as_ref()
         ],
-        bump
+        bump = global_config_account.bump
     )]
     pub global_config_account: Account<'info, GlobalConfig>,
     #[account(
@@ -33,6 +37,7 @@ pub struct RollbackPosition<'info> {
             possition_index.to_le_bytes().as_ref()
         ],
         bump,
+        constraint = user_account.receipt_mint.is_none() @ ErrorCode::PositionHasReceipt
     )]
     pub user_account: Account<'info, UserPosition>,
     /// CHECK: PDA authority of position
@@ -42,42 +47,69 @@ pub struct RollbackPosition<'info> {
     )]
     pub user: AccountInfo<'info>,
     #[account(
-        constraint = global_config_account.usdc_mint == usdc_mint.key()
+        constraint = user_account.pay_mint == usdc_mint.key() @ ErrorCode::InvalidPayMint
     )]
-    pub usdc_mint: Account<'info, Mint>,
+    pub usdc_mint: InterfaceAccount<'info, Mint>,
     #[account(
         mut,
         associated_token::mint = usdc_mint,
-        associated_token::authority = user
+        associated_token::authority = user,
+        associated_token::token_program = token_program,
     )]
-    pub user_usdc_ata: Account<'info, TokenAccount>,
+    pub user_usdc_ata: InterfaceAccount<'info, TokenAccount>,
 
     #[account(
         mut,
-        seeds = [VAULT_USDC_SEED],
+        seeds = [
+            VAULT_USDC_SEED,
+            global_config_account.key().as_ref(),
+            global_config_account.pay_vault_seed(&usdc_mint.key())
+        ],
         bump,
         token::mint = usdc_mint,
         token::authority = global_config_account,
+        token::token_program = token_program,
     )]
-    pub program_usdc_token_account: Account<'info, TokenAccount>,
+    pub program_usdc_token_account: InterfaceAccount<'info, TokenAccount>,
     #[account(
         mut,
-        seeds = [USER_SUMMARY_SEED, user.key().as_ref()],
+        seeds = [
+            USER_SUMMARY_SEED,
+            global_config_account.key().as_ref(),
+            user.key().as_ref()
+        ],
         bump,
         constraint = user_summary.authority == user.key()
     )]
     pub user_summary: Account<'info, UserSummary>,
-    pub token_program: Program<'info, Token>,
+    #[account(
+        seeds = [LIQUIDITY_CURVE_SEED, global_config_account.key().as_ref()],
+        bump = liquidity_curve.bump
+    )]
+    pub liquidity_curve: Option<Account<'info, LiquidityCurve>>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
 }
 
 impl<'info> RollbackPosition<'info> {
     pub fn apply(ctx: &mut Context<RollbackPosition>, _possition_index: u64) -> Result<()> {
+        ctx.accounts
+            .global_config_account
+            .require_not_paused(PAUSE_REFUND)?;
+        // the sale outcome is settled at sale_end_ts, a later rollback could flip it to failed
+        require!(
+            Clock::get()?.unix_timestamp < ctx.accounts.global_config_account.sale_end_ts,
+            ErrorCode::SaleEnded
+        );
         require!(
             ctx.accounts.program_usdc_token_account.amount > 0,
             ErrorCode::WithdrawNotAllowed
         );
+        require!(
+            ctx.accounts.user_account.claimed_amount == 0,
+            ErrorCode::PositionPartiallyClaimed
+        );
 
         let usdc_amount = ctx.accounts.user_account.usdc_spent;
         let walien_amount = ctx.accounts.user_account.walien_allocation;
@@ -85,6 +117,18 @@ impl<'info> RollbackPosition<'info> {
         ctx.accounts
             .global_config_account
             .available_for_swap_in_usdc += usdc_amount;
+        ctx.accounts.global_config_account.initial_sqrt_price_x64 =
+            calculate_refund_price_from_config(
+                &ctx.accounts.global_config_account,
+                ctx.accounts.liquidity_curve.as_deref(),
+                walien_amount,
+            )?;
+        ctx.accounts.global_config_account.total_usdc_raised = ctx
+            .accounts
+            .global_config_account
+            .total_usdc_raised
+            .checked_sub(usdc_amount)
+            .ok_or(ErrorCode::AmountCalcOverflow)?;
 
         ctx.accounts.user_summary.total_usdc_locked = ctx
             .accounts
@@ -99,20 +143,28 @@ impl<'info> RollbackPosition<'info> {
             .checked_sub(walien_amount)
             .ok_or(ErrorCode::UserSummaryUnderflow)?;
 
-        let cpi_accounts = token::Transfer {
+        let cpi_accounts = TransferChecked {
             from: ctx.accounts.program_usdc_token_account.to_account_info(),
             to: ctx.accounts.user_usdc_ata.to_account_info(),
             authority: ctx.accounts.global_config_account.to_account_info(),
+            mint: ctx.accounts.usdc_mint.to_account_info(),
         };
+        let pool_id_bytes = ctx.accounts.global_config_account.pool_id.to_le_bytes();
         let seeds = &[
             CONFIG_SEED.as_ref(),
+            pool_id_bytes.as_ref(),
             &[ctx.accounts.global_config_account.bump],
         ];
         let signer = &[&seeds[..]];
         let cpi_program = ctx.accounts.token_program.to_account_info();
         let transfer_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
 
-        token::transfer(transfer_ctx, usdc_amount)?;
+        let pay_amount = to_pay_amount(usdc_amount, ctx.accounts.usdc_mint.decimals, false)?;
+        token_interface::transfer_checked(
+            transfer_ctx,
+            pay_amount,
+            ctx.accounts.usdc_mint.decimals,
+        )?;
 
         ctx.accounts.user_account.walien_allocation = 0;
 
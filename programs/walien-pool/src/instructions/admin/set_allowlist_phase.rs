@@ -0,0 +1,26 @@
+use crate::{constants::CONFIG_SEED, state::GlobalConfig};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetAllowlistPhase<'info> {
+    #[account(
+        mut,
+        seeds = [
+            CONFIG_SEED,
+            global_config_account.pool_id.to_le_bytes().as_ref()
+        ],
+        bump = global_config_account.bump
+    )]
+    pub global_config_account: Account<'info, GlobalConfig>,
+    #[account(
+        mut,
+        constraint = global_config_account.admin == admin.key())]
+    pub admin: Signer<'info>,
+}
+
+impl<'info> SetAllowlistPhase<'info> {
+    pub fn apply(ctx: &mut Context<SetAllowlistPhase>, is_allowlist_phase: bool) -> Result<()> {
+        ctx.accounts.global_config_account.is_allowlist_phase = is_allowlist_phase;
+        Ok(())
+    }
+}
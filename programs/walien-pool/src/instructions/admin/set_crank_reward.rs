@@ -0,0 +1,97 @@
+use crate::{
+    constants::{CONFIG_SEED, VAULT_WALIEN_SEED},
+    errors::ErrorCode,
+    state::GlobalConfig,
+    token_extensions::transfer_checked_with_hook,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token_2022::{Token2022, TransferChecked};
+use anchor_spl::token_interface::{Mint as Token2022Mint, TokenAccount as Token2022TokenAccount};
+
+#[derive(Accounts)]
+pub struct SetCrankReward<'info> {
+    #[account(
+        mut,
+        seeds = [
+            CONFIG_SEED,
+            global_config_account.pool_id.to_le_bytes().as_ref()
+        ],
+        bump = global_config_account.bump
+    )]
+    pub global_config_account: Account<'info, GlobalConfig>,
+    #[account(
+        mut,
+        constraint = global_config_account.admin == admin.key())]
+    pub admin: Signer<'info>,
+    #[account(
+        constraint = global_config_account.walien_mint == Some(walien_mint.key())
+    )]
+    pub walien_mint: InterfaceAccount<'info, Token2022Mint>,
+    #[account(
+        mut,
+        associated_token::mint = walien_mint,
+        associated_token::authority = admin,
+        associated_token::token_program = token_program,
+    )]
+    pub admin_walien_token_account: InterfaceAccount<'info, Token2022TokenAccount>,
+    #[account(
+        mut,
+        seeds = [VAULT_WALIEN_SEED, global_config_account.key().as_ref()],
+        bump,
+        token::mint = walien_mint,
+        token::authority = global_config_account,
+        token::token_program = token_program,
+    )]
+    pub program_walien_token_account: InterfaceAccount<'info, Token2022TokenAccount>,
+    pub token_program: Program<'info, Token2022>,
+}
+
+impl<'info> SetCrankReward<'info> {
+    /// Lamport rewards come out of SOL sent to the config account. wALIEN rewards come out of
+    /// `crank_walien_budget`, which only grows by what `budget_deposit` actually brings into the
+    /// vault, so it never eats into the allocations. Remaining accounts go to the transfer hook.
+    pub fn apply(
+        ctx: &mut Context<'_, '_, '_, 'info, SetCrankReward<'info>>,
+        crank_reward_lamports: u64,
+        crank_reward_walien: u64,
+        budget_deposit: u64,
+    ) -> Result<()> {
+        if budget_deposit > 0 {
+            let balance_before = ctx.accounts.program_walien_token_account.amount;
+            let cpi_accounts = TransferChecked {
+                from: ctx.accounts.admin_walien_token_account.to_account_info(),
+                to: ctx.accounts.program_walien_token_account.to_account_info(),
+                authority: ctx.accounts.admin.to_account_info(),
+                mint: ctx.accounts.walien_mint.to_account_info(),
+            };
+            let cpi_program = ctx.accounts.token_program.to_account_info();
+            let transfer_ctx = CpiContext::new(cpi_program, cpi_accounts)
+                .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+            transfer_checked_with_hook(
+                transfer_ctx,
+                budget_deposit,
+                ctx.accounts.walien_mint.decimals,
+            )?;
+
+            // with a transfer fee the vault receives less than budget_deposit
+            ctx.accounts.program_walien_token_account.reload()?;
+            let received = ctx
+                .accounts
+                .program_walien_token_account
+                .amount
+                .checked_sub(balance_before)
+                .ok_or(ErrorCode::AmountCalcOverflow)?;
+            ctx.accounts.global_config_account.crank_walien_budget = ctx
+                .accounts
+                .global_config_account
+                .crank_walien_budget
+                .checked_add(received)
+                .ok_or(ErrorCode::AmountCalcOverflow)?;
+        }
+
+        let cfg = &mut ctx.accounts.global_config_account;
+        cfg.crank_reward_lamports = crank_reward_lamports;
+        cfg.crank_reward_walien = crank_reward_walien;
+        Ok(())
+    }
+}
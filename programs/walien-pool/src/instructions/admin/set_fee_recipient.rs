@@ -0,0 +1,28 @@
+use crate::{constants::CONFIG_SEED, state::GlobalConfig};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetFeeRecipient<'info> {
+    #[account(
+        mut,
+        seeds = [
+            CONFIG_SEED,
+            global_config_account.pool_id.to_le_bytes().as_ref()
+        ],
+        bump = global_config_account.bump
+    )]
+    pub global_config_account: Account<'info, GlobalConfig>,
+    #[account(
+        mut,
+        constraint = global_config_account.admin == admin.key())]
+    pub admin: Signer<'info>,
+    /// CHECK: only its USDC token account receives fees in `collect_fees`.
+    pub fee_recipient: UncheckedAccount<'info>,
+}
+
+impl<'info> SetFeeRecipient<'info> {
+    pub fn apply(ctx: &mut Context<SetFeeRecipient>) -> Result<()> {
+        ctx.accounts.global_config_account.fee_recipient = ctx.accounts.fee_recipient.key();
+        Ok(())
+    }
+}
@@ -0,0 +1,48 @@
+use crate::{
+    constants::CONFIG_SEED, errors::ErrorCode, orca_math::MAX_FEE_RATE, state::GlobalConfig,
+};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetFeeSchedule<'info> {
+    #[account(
+        mut,
+        seeds = [
+            CONFIG_SEED,
+            global_config_account.pool_id.to_le_bytes().as_ref()
+        ],
+        bump = global_config_account.bump
+    )]
+    pub global_config_account: Account<'info, GlobalConfig>,
+    #[account(
+        mut,
+        constraint = global_config_account.admin == admin.key())]
+    pub admin: Signer<'info>,
+}
+
+impl<'info> SetFeeSchedule<'info> {
+    /// `fee_decay_secs == 0` turns the schedule off.
+    pub fn apply(
+        ctx: &mut Context<SetFeeSchedule>,
+        launch_fee_rate: u16,
+        fee_decay_secs: i64,
+        fee_decay_steps: u16,
+    ) -> Result<()> {
+        let cfg = &mut ctx.accounts.global_config_account;
+        require!(
+            !cfg.is_sale_open(Clock::get()?.unix_timestamp),
+            ErrorCode::SaleIsActive
+        );
+        require!(launch_fee_rate <= MAX_FEE_RATE, ErrorCode::FeeRateTooHigh);
+        require!(
+            fee_decay_secs == 0 || launch_fee_rate >= cfg.fee_bps,
+            ErrorCode::InvalidFeeSchedule
+        );
+        require!(fee_decay_secs >= 0, ErrorCode::InvalidFeeSchedule);
+
+        cfg.launch_fee_rate = launch_fee_rate;
+        cfg.fee_decay_secs = fee_decay_secs;
+        cfg.fee_decay_steps = fee_decay_steps;
+        Ok(())
+    }
+}
@@ -0,0 +1,83 @@
+use crate::{
+    constants::{CONFIG_SEED, LIQUIDITY_CURVE_SEED, MAX_LIQUIDITY_RANGES},
+    errors::ErrorCode,
+    events::LiquidityCurveUpdatedEvent,
+    state::{GlobalConfig, LiquidityCurve, LiquidityRange},
+    utils::validate_pool_params,
+};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetLiquidityCurve<'info> {
+    #[account(
+        mut,
+        seeds = [
+            CONFIG_SEED,
+            global_config_account.pool_id.to_le_bytes().as_ref()
+        ],
+        bump = global_config_account.bump
+    )]
+    pub global_config_account: Account<'info, GlobalConfig>,
+    #[account(
+        mut,
+        constraint = global_config_account.admin == admin.key())]
+    pub admin: Signer<'info>,
+    #[account(
+        init_if_needed,
+        space = LiquidityCurve::SIZE,
+        payer = admin,
+        seeds = [LIQUIDITY_CURVE_SEED, global_config_account.key().as_ref()],
+        bump
+    )]
+    pub liquidity_curve: Account<'info, LiquidityCurve>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> SetLiquidityCurve<'info> {
+    /// Empty `ranges` switches the pool back to the single `[price, tick_upper]` range.
+    pub fn apply(ctx: &mut Context<SetLiquidityCurve>, ranges: Vec<LiquidityRange>) -> Result<()> {
+        let cfg = &mut ctx.accounts.global_config_account;
+        require!(
+            !cfg.is_sale_open(Clock::get()?.unix_timestamp),
+            ErrorCode::SaleIsActive
+        );
+        // refunds walk the curve the allocations were bought on
+        require!(!cfg.has_positions(), ErrorCode::PoolHasPositions);
+        require!(
+            ranges.len() <= MAX_LIQUIDITY_RANGES,
+            ErrorCode::InvalidLiquidityCurve
+        );
+
+        if let Some(first) = ranges.first() {
+            validate_pool_params(
+                cfg.initial_sqrt_price_x64,
+                first.tick_upper,
+                first.liquidity,
+                cfg.fee_bps,
+            )?;
+        }
+        for pair in ranges.windows(2) {
+            require!(
+                pair[0].tick_upper < pair[1].tick_upper,
+                ErrorCode::InvalidLiquidityCurve
+            );
+            require!(pair[1].liquidity > 0, ErrorCode::LiquidityZero);
+        }
+
+        if let Some(last) = ranges.last() {
+            cfg.tick_upper = last.tick_upper;
+        }
+        cfg.has_liquidity_curve = !ranges.is_empty();
+
+        let curve = &mut ctx.accounts.liquidity_curve;
+        curve.global_config = cfg.key();
+        curve.ranges = ranges.clone();
+        curve.bump = ctx.bumps.liquidity_curve;
+
+        emit!(LiquidityCurveUpdatedEvent {
+            pool_id: cfg.pool_id,
+            ranges,
+        });
+        Ok(())
+    }
+}
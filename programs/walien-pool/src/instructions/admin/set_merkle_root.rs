@@ -2,26 +2,25 @@ use crate::{constants::CONFIG_SEED, state::GlobalConfig};
 use anchor_lang::prelude::*;
 
 #[derive(Accounts)]
-pub struct TransferAdminAuthority<'info> {
+pub struct SetMerkleRoot<'info> {
     #[account(
         mut,
         seeds = [
-            CONFIG_SEED
+            CONFIG_SEED,
+            global_config_account.pool_id.to_le_bytes().as_ref()
         ],
-        bump
+        bump = global_config_account.bump
     )]
     pub global_config_account: Account<'info, GlobalConfig>,
     #[account(
         mut,
         constraint = global_config_account.admin == admin.key())]
     pub admin: Signer<'info>,
-    /// CHECK: no need to check here.
-    pub new_admin_authority: UncheckedAccount<'info>,
 }
 
-impl<'info> TransferAdminAuthority<'info> {
-    pub fn apply(ctx: &mut Context<TransferAdminAuthority>) -> Result<()> {
-        ctx.accounts.global_config_account.admin = ctx.accounts.new_admin_authority.key();
+impl<'info> SetMerkleRoot<'info> {
+    pub fn apply(ctx: &mut Context<SetMerkleRoot>, merkle_root: Option<[u8; 32]>) -> Result<()> {
+        ctx.accounts.global_config_account.merkle_root = merkle_root;
         Ok(())
     }
 }
@@ -0,0 +1,32 @@
+use crate::{
+    constants::{CONFIG_SEED, PAUSE_ALL},
+    errors::ErrorCode,
+    state::GlobalConfig,
+};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetPauseFlags<'info> {
+    #[account(
+        mut,
+        seeds = [
+            CONFIG_SEED,
+            global_config_account.pool_id.to_le_bytes().as_ref()
+        ],
+        bump = global_config_account.bump
+    )]
+    pub global_config_account: Account<'info, GlobalConfig>,
+    #[account(
+        mut,
+        constraint = global_config_account.admin == admin.key())]
+    pub admin: Signer<'info>,
+}
+
+impl<'info> SetPauseFlags<'info> {
+    /// Replaces the whole set of `PAUSE_*` bits, 0 resumes every path.
+    pub fn apply(ctx: &mut Context<SetPauseFlags>, pause_flags: u8) -> Result<()> {
+        require!(pause_flags & !PAUSE_ALL == 0, ErrorCode::InvalidPauseFlags);
+        ctx.accounts.global_config_account.pause_flags = pause_flags;
+        Ok(())
+    }
+}
@@ -0,0 +1,30 @@
+use crate::{constants::CONFIG_SEED, state::GlobalConfig};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetPositionReceipts<'info> {
+    #[account(
+        mut,
+        seeds = [
+            CONFIG_SEED,
+            global_config_account.pool_id.to_le_bytes().as_ref()
+        ],
+        bump = global_config_account.bump
+    )]
+    pub global_config_account: Account<'info, GlobalConfig>,
+    #[account(
+        mut,
+        constraint = global_config_account.admin == admin.key())]
+    pub admin: Signer<'info>,
+}
+
+impl<'info> SetPositionReceipts<'info> {
+    /// Only affects later buys, existing positions keep how they were bought.
+    pub fn apply(
+        ctx: &mut Context<SetPositionReceipts>,
+        mint_position_receipts: bool,
+    ) -> Result<()> {
+        ctx.accounts.global_config_account.mint_position_receipts = mint_position_receipts;
+        Ok(())
+    }
+}
@@ -0,0 +1,36 @@
+use crate::{constants::CONFIG_SEED, errors::ErrorCode, state::GlobalConfig};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetPurchaseLimits<'info> {
+    #[account(
+        mut,
+        seeds = [
+            CONFIG_SEED,
+            global_config_account.pool_id.to_le_bytes().as_ref()
+        ],
+        bump = global_config_account.bump
+    )]
+    pub global_config_account: Account<'info, GlobalConfig>,
+    #[account(
+        mut,
+        constraint = global_config_account.admin == admin.key())]
+    pub admin: Signer<'info>,
+}
+
+impl<'info> SetPurchaseLimits<'info> {
+    pub fn apply(
+        ctx: &mut Context<SetPurchaseLimits>,
+        min_usdc_per_buy: u64,
+        max_usdc_per_buy: u64,
+    ) -> Result<()> {
+        require!(
+            max_usdc_per_buy == 0 || min_usdc_per_buy <= max_usdc_per_buy,
+            ErrorCode::InvalidPurchaseLimits
+        );
+
+        ctx.accounts.global_config_account.min_usdc_per_buy = min_usdc_per_buy;
+        ctx.accounts.global_config_account.max_usdc_per_buy = max_usdc_per_buy;
+        Ok(())
+    }
+}
@@ -6,9 +6,10 @@ pub struct SetSaleActivity<'info> {
     #[account(
         mut,
         seeds = [
-            CONFIG_SEED
+            CONFIG_SEED,
+            global_config_account.pool_id.to_le_bytes().as_ref()
         ],
-        bump
+        bump = global_config_account.bump
     )]
     pub global_config_account: Account<'info, GlobalConfig>,
     #[account(
@@ -0,0 +1,39 @@
+use crate::{constants::CONFIG_SEED, state::GlobalConfig};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetSchedule<'info> {
+    #[account(
+        mut,
+        seeds = [
+            CONFIG_SEED,
+            global_config_account.pool_id.to_le_bytes().as_ref()
+        ],
+        bump = global_config_account.bump
+    )]
+    pub global_config_account: Account<'info, GlobalConfig>,
+    #[account(
+        mut,
+        constraint = global_config_account.admin == admin.key())]
+    pub admin: Signer<'info>,
+}
+
+impl<'info> SetSchedule<'info> {
+    /// Windows that already started must be passed unchanged, see `validate_reschedule`.
+    pub fn apply(
+        ctx: &mut Context<SetSchedule>,
+        sale_start_ts: i64,
+        sale_end_ts: i64,
+        claim_start_ts: i64,
+    ) -> Result<()> {
+        let now_ts = Clock::get()?.unix_timestamp;
+        let cfg = &mut ctx.accounts.global_config_account;
+
+        cfg.validate_reschedule(sale_start_ts, sale_end_ts, claim_start_ts, now_ts)?;
+
+        cfg.sale_start_ts = sale_start_ts;
+        cfg.sale_end_ts = sale_end_ts;
+        cfg.claim_start_ts = claim_start_ts;
+        Ok(())
+    }
+}
@@ -0,0 +1,32 @@
+use crate::{constants::CONFIG_SEED, errors::ErrorCode, state::GlobalConfig};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetSoftCap<'info> {
+    #[account(
+        mut,
+        seeds = [
+            CONFIG_SEED,
+            global_config_account.pool_id.to_le_bytes().as_ref()
+        ],
+        bump = global_config_account.bump
+    )]
+    pub global_config_account: Account<'info, GlobalConfig>,
+    #[account(
+        mut,
+        constraint = global_config_account.admin == admin.key())]
+    pub admin: Signer<'info>,
+}
+
+impl<'info> SetSoftCap<'info> {
+    /// The sale outcome is final once the window ended, so the cap is locked from then on.
+    pub fn apply(ctx: &mut Context<SetSoftCap>, soft_cap_usdc: u64) -> Result<()> {
+        let now_ts = Clock::get()?.unix_timestamp;
+        let cfg = &mut ctx.accounts.global_config_account;
+
+        require!(now_ts < cfg.sale_end_ts, ErrorCode::SaleEnded);
+
+        cfg.soft_cap_usdc = soft_cap_usdc;
+        Ok(())
+    }
+}
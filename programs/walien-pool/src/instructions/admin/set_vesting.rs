@@ -0,0 +1,40 @@
+use crate::{constants::CONFIG_SEED, errors::ErrorCode, state::GlobalConfig};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetVesting<'info> {
+    #[account(
+        mut,
+        seeds = [
+            CONFIG_SEED,
+            global_config_account.pool_id.to_le_bytes().as_ref()
+        ],
+        bump = global_config_account.bump
+    )]
+    pub global_config_account: Account<'info, GlobalConfig>,
+    #[account(
+        mut,
+        constraint = global_config_account.admin == admin.key())]
+    pub admin: Signer<'info>,
+}
+
+impl<'info> SetVesting<'info> {
+    pub fn apply(
+        ctx: &mut Context<SetVesting>,
+        vesting_cliff_secs: i64,
+        vesting_duration_secs: i64,
+    ) -> Result<()> {
+        let now_ts = Clock::get()?.unix_timestamp;
+        let cfg = &mut ctx.accounts.global_config_account;
+
+        require!(cfg.claim_start_ts > now_ts, ErrorCode::WindowAlreadyStarted);
+        require!(
+            vesting_cliff_secs >= 0 && vesting_cliff_secs <= vesting_duration_secs,
+            ErrorCode::InvalidVestingSchedule
+        );
+
+        cfg.vesting_cliff_secs = vesting_cliff_secs;
+        cfg.vesting_duration_secs = vesting_duration_secs;
+        Ok(())
+    }
+}
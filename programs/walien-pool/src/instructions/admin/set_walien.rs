@@ -10,6 +10,7 @@ use crate::{
     constants::{CONFIG_SEED, VAULT_WALIEN_SEED},
     errors::ErrorCode,
     state::GlobalConfig,
+    token_extensions::validate_walien_mint,
 };
 
 #[derive(Accounts)]
@@ -17,9 +18,10 @@ pub struct SetWalien<'info> {
     #[account(
         mut,
         seeds = [
-            CONFIG_SEED
+            CONFIG_SEED,
+            global_config_account.pool_id.to_le_bytes().as_ref()
         ],
-        bump
+        bump = global_config_account.bump
     )]
     pub global_config_account: Account<'info, GlobalConfig>,
     #[account(
@@ -31,7 +33,7 @@ pub struct SetWalien<'info> {
     #[account(
         init,
         payer = admin,
-        seeds = [VAULT_WALIEN_SEED],
+        seeds = [VAULT_WALIEN_SEED, global_config_account.key().as_ref()],
         bump,
         token::mint = walien_mint,
         token::authority = global_config_account,
@@ -48,6 +50,8 @@ impl<'info> SetWalien<'info> {
             ctx.accounts.walien_mint.decimals == WALIEN_DECIMALS,
             ErrorCode::InvalidWalienDecimals
         );
+        let walien_mint_info = ctx.accounts.walien_mint.to_account_info();
+        validate_walien_mint(&walien_mint_info.try_borrow_data()?)?;
 
         ctx.accounts.global_config_account.walien_mint = Some(ctx.accounts.walien_mint.key());
 
@@ -0,0 +1,36 @@
+use crate::{constants::CONFIG_SEED, errors::ErrorCode, state::GlobalConfig};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetWalletCaps<'info> {
+    #[account(
+        mut,
+        seeds = [
+            CONFIG_SEED,
+            global_config_account.pool_id.to_le_bytes().as_ref()
+        ],
+        bump = global_config_account.bump
+    )]
+    pub global_config_account: Account<'info, GlobalConfig>,
+    #[account(
+        mut,
+        constraint = global_config_account.admin == admin.key())]
+    pub admin: Signer<'info>,
+}
+
+impl<'info> SetWalletCaps<'info> {
+    pub fn apply(
+        ctx: &mut Context<SetWalletCaps>,
+        min_usdc_per_wallet: u64,
+        max_usdc_per_wallet: u64,
+    ) -> Result<()> {
+        require!(
+            max_usdc_per_wallet == 0 || min_usdc_per_wallet <= max_usdc_per_wallet,
+            ErrorCode::InvalidWalletCaps
+        );
+
+        ctx.accounts.global_config_account.min_usdc_per_wallet = min_usdc_per_wallet;
+        ctx.accounts.global_config_account.max_usdc_per_wallet = max_usdc_per_wallet;
+        Ok(())
+    }
+}
@@ -0,0 +1,66 @@
+use crate::{
+    constants::CONFIG_SEED, errors::ErrorCode, events::PoolParamsUpdatedEvent, state::GlobalConfig,
+    utils::validate_pool_params,
+};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct UpdatePoolParams<'info> {
+    #[account(
+        mut,
+        seeds = [
+            CONFIG_SEED,
+            global_config_account.pool_id.to_le_bytes().as_ref()
+        ],
+        bump = global_config_account.bump
+    )]
+    pub global_config_account: Account<'info, GlobalConfig>,
+    #[account(
+        mut,
+        constraint = global_config_account.admin == admin.key())]
+    pub admin: Signer<'info>,
+}
+
+impl<'info> UpdatePoolParams<'info> {
+    pub fn apply(
+        ctx: &mut Context<UpdatePoolParams>,
+        initial_sqrt_price_x64: u128,
+        tick_upper: i32,
+        available_for_swap_in_usdc: u64,
+        liquidity: u128,
+        fee_bps: u16,
+    ) -> Result<()> {
+        let cfg = &mut ctx.accounts.global_config_account;
+        require!(
+            !cfg.is_sale_open(Clock::get()?.unix_timestamp),
+            ErrorCode::SaleIsActive
+        );
+        // the floor and the curve are what existing allocations and refunds are priced on
+        require!(!cfg.has_positions(), ErrorCode::PoolHasPositions);
+        // the curve's ranges were built on the current price, the admin drops them explicitly
+        require!(!cfg.has_liquidity_curve, ErrorCode::LiquidityCurveSet);
+        validate_pool_params(initial_sqrt_price_x64, tick_upper, liquidity, fee_bps)?;
+
+        emit!(PoolParamsUpdatedEvent {
+            pool_id: cfg.pool_id,
+            old_initial_sqrt_price_x64: cfg.initial_sqrt_price_x64,
+            new_initial_sqrt_price_x64: initial_sqrt_price_x64,
+            old_tick_upper: cfg.tick_upper,
+            new_tick_upper: tick_upper,
+            old_available_for_swap_in_usdc: cfg.available_for_swap_in_usdc,
+            new_available_for_swap_in_usdc: available_for_swap_in_usdc,
+            old_liquidity: cfg.liqudity,
+            new_liquidity: liquidity,
+            old_fee_bps: cfg.fee_bps,
+            new_fee_bps: fee_bps,
+        });
+
+        cfg.initial_sqrt_price_x64 = initial_sqrt_price_x64;
+        cfg.floor_sqrt_price_x64 = initial_sqrt_price_x64;
+        cfg.tick_upper = tick_upper;
+        cfg.available_for_swap_in_usdc = available_for_swap_in_usdc;
+        cfg.liqudity = liquidity;
+        cfg.fee_bps = fee_bps;
+        Ok(())
+    }
+}
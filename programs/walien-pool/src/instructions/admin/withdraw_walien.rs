@@ -1,12 +1,13 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token_2022::{Token2022, TransferChecked, transfer_checked};
+use anchor_spl::token_2022::{Token2022, TransferChecked};
 use anchor_spl::token_interface::{
     Mint as Token2022Mint, TokenAccount as Token2022TokenAccount,
 };
 
 use crate::{
-    constants::{CONFIG_SEED, VAULT_WALIEN_SEED},
+    constants::{CONFIG_SEED, PAUSE_ADMIN_WITHDRAW, VAULT_WALIEN_SEED},
     state::GlobalConfig,
+    token_extensions::{amount_with_transfer_fee, transfer_checked_with_hook},
 };
 
 #[derive(Accounts)]
@@ -14,9 +15,10 @@ pub struct WithdrawWalien<'info> {
     #[account(
         mut,
         seeds = [
-            CONFIG_SEED
+            CONFIG_SEED,
+            global_config_account.pool_id.to_le_bytes().as_ref()
         ],
-        bump
+        bump = global_config_account.bump
     )]
     pub global_config_account: Account<'info, GlobalConfig>,
     #[account(
@@ -36,7 +38,7 @@ pub struct WithdrawWalien<'info> {
     pub admin_walien_token_account: InterfaceAccount<'info, Token2022TokenAccount>,
     #[account(
         mut,
-        seeds = [VAULT_WALIEN_SEED],
+        seeds = [VAULT_WALIEN_SEED, global_config_account.key().as_ref()],
         bump,
         token::mint = walien_mint,
         token::authority = global_config_account,
@@ -47,11 +49,23 @@ pub struct WithdrawWalien<'info> {
 }
 
 impl<'info> WithdrawWalien<'info> {
-    pub fn apply(ctx: &mut Context<WithdrawWalien>) -> Result<()> {
-        let balance = ctx.accounts.program_walien_token_account.amount;
+    pub fn apply(ctx: &mut Context<'_, '_, '_, 'info, WithdrawWalien<'info>>) -> Result<()> {
+        ctx.accounts
+            .global_config_account
+            .require_not_paused(PAUSE_ADMIN_WITHDRAW)?;
+        // owed referral rewards stay behind, with the fee their payout costs the vault
+        let owed = ctx.accounts.global_config_account.referral_walien_owed;
+        let owed_fee =
+            amount_with_transfer_fee(&ctx.accounts.walien_mint.to_account_info(), owed)? - owed;
+        let amount = ctx
+            .accounts
+            .global_config_account
+            .withdrawable_walien(ctx.accounts.program_walien_token_account.amount, owed_fee);
 
+        let pool_id_bytes = ctx.accounts.global_config_account.pool_id.to_le_bytes();
         let seeds = &[
             CONFIG_SEED.as_ref(),
+            pool_id_bytes.as_ref(),
             &[ctx.accounts.global_config_account.bump],
         ];
         let signer = &[&seeds[..]];
@@ -62,8 +76,12 @@ impl<'info> WithdrawWalien<'info> {
             mint: ctx.accounts.walien_mint.to_account_info(),
         };
         let cpi_program = ctx.accounts.token_program.to_account_info();
-        let transfer_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
-        transfer_checked(transfer_ctx, balance, ctx.accounts.walien_mint.decimals)?;
+        let transfer_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer)
+            .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+        transfer_checked_with_hook(transfer_ctx, amount, ctx.accounts.walien_mint.decimals)?;
+        // the unreserved budgets left the vault with everything else
+        ctx.accounts.global_config_account.crank_walien_budget = 0;
+        ctx.accounts.global_config_account.referral_walien_budget = 0;
 
         Ok(())
     }
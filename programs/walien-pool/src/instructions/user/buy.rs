@@ -1,21 +1,32 @@
 use crate::{
-    constants::{CONFIG_SEED, USDC_DECIMALS, USER_SUMMARY_SEED, VAULT_USDC_SEED},
+    constants::{
+        CONFIG_SEED, LIQUIDITY_CURVE_SEED, PAY_MINT_SEED, RECEIPT_MINT_SEED, REFERRER_SEED,
+        USER_SUMMARY_SEED, VAULT_USDC_SEED,
+    },
     errors::ErrorCode,
-    events::BuyEvent,
-    state::{GlobalConfig, UserPosition, UserSummary},
-    utils::calculate_swap_from_config,
+    events::{BuyEvent, ReferralEvent},
+    orca_math::SwapStepComputation,
+    receipt::{mint_receipt, ReceiptAccounts},
+    state::{
+        AllowlistProof, GlobalConfig, LiquidityCurve, PayMint, Referrer, UserPosition, UserSummary,
+    },
+    token_extensions::amount_with_transfer_fee,
+    utils::{calculate_exact_out_swap_from_config, calculate_swap_from_config, to_pay_amount},
 };
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Mint, Token, TokenAccount};
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_2022::Token2022;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
 
 #[derive(Accounts)]
 pub struct Buy<'info> {
     #[account(
         mut,
         seeds = [
-            CONFIG_SEED
+            CONFIG_SEED,
+            global_config_account.pool_id.to_le_bytes().as_ref()
         ],
-        bump
+        bump = global_config_account.bump
     )]
     pub global_config_account: Account<'info, GlobalConfig>,
     #[account(mut)]
@@ -35,60 +46,162 @@ pub struct Buy<'info> {
         init_if_needed,
         space = UserSummary::SIZE,
         payer = user,
-        seeds = [USER_SUMMARY_SEED, user.key().as_ref()],
+        seeds = [
+            USER_SUMMARY_SEED,
+            global_config_account.key().as_ref(),
+            user.key().as_ref()
+        ],
         bump
     )]
     pub user_summary: Account<'info, UserSummary>,
+    /// `usdc_mint` or a registered pay mint (then `pay_mint` is required).
     #[account(
         constraint = global_config_account.usdc_mint == usdc_mint.key()
+            || pay_mint.is_some() @ ErrorCode::InvalidPayMint
+    )]
+    pub usdc_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        seeds = [
+            PAY_MINT_SEED,
+            global_config_account.key().as_ref(),
+            usdc_mint.key().as_ref()
+        ],
+        bump = pay_mint.bump
     )]
-    pub usdc_mint: Account<'info, Mint>,
+    pub pay_mint: Option<Account<'info, PayMint>>,
     #[account(
         mut,
         associated_token::mint = usdc_mint,
-        associated_token::authority = user
+        associated_token::authority = user,
+        associated_token::token_program = token_program,
     )]
-    pub user_usdc_ata: Account<'info, TokenAccount>,
+    pub user_usdc_ata: InterfaceAccount<'info, TokenAccount>,
 
     #[account(
         mut,
-        seeds = [VAULT_USDC_SEED],
+        seeds = [
+            VAULT_USDC_SEED,
+            global_config_account.key().as_ref(),
+            global_config_account.pay_vault_seed(&usdc_mint.key())
+        ],
         bump,
         token::mint = usdc_mint,
         token::authority = global_config_account,
+        token::token_program = token_program,
+    )]
+    pub program_usdc_token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [
+            REFERRER_SEED,
+            global_config_account.key().as_ref(),
+            referrer.authority.as_ref()
+        ],
+        bump = referrer.bump,
+        constraint = referrer.authority != user.key() @ ErrorCode::SelfReferral
+    )]
+    pub referrer: Option<Account<'info, Referrer>>,
+    #[account(
+        seeds = [LIQUIDITY_CURVE_SEED, global_config_account.key().as_ref()],
+        bump = liquidity_curve.bump
     )]
-    pub program_usdc_token_account: Account<'info, TokenAccount>,
-    pub token_program: Program<'info, Token>,
+    pub liquidity_curve: Option<Account<'info, LiquidityCurve>>,
+    /// CHECK: receipt NFT mint, created by `receipt::mint_receipt`; required when
+    /// `mint_position_receipts` is on
+    #[account(
+        mut,
+        seeds = [RECEIPT_MINT_SEED, user_account.key().as_ref()],
+        bump
+    )]
+    pub receipt_mint: Option<UncheckedAccount<'info>>,
+    /// CHECK: buyer's receipt ATA, created by `receipt::mint_receipt`
+    #[account(mut)]
+    pub user_receipt_token_account: Option<UncheckedAccount<'info>>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub token_program_2022: Option<Program<'info, Token2022>>,
+    pub associated_token_program: Option<Program<'info, AssociatedToken>>,
     pub system_program: Program<'info, System>,
 }
 
 impl<'info> Buy<'info> {
-    pub fn apply(ctx: &mut Context<Buy>, amount: u64, min_tokens_out: u64) -> Result<()> {
+    pub fn apply(
+        ctx: &mut Context<Buy>,
+        amount: u64,
+        min_tokens_out: u64,
+        allowlist_proof: Option<AllowlistProof>,
+    ) -> Result<()> {
+        ctx.accounts
+            .global_config_account
+            .require_sale_open(Clock::get()?.unix_timestamp)?;
+
+        let calculation_result = calculate_swap_from_config(
+            &ctx.accounts.global_config_account,
+            ctx.accounts.liquidity_curve.as_deref(),
+            amount,
+        )?;
         require!(
-            ctx.accounts.global_config_account.is_sale_active,
-            ErrorCode::SaleNotActive
+            calculation_result.amount_out >= min_tokens_out,
+            ErrorCode::SlippageExceeded
         );
 
-        let cfg = &mut ctx.accounts.global_config_account;
+        Self::settle(ctx, calculation_result, allowlist_proof)
+    }
 
-        let calculation_result = calculate_swap_from_config(cfg, amount)?;
-        let transfer_amount = calculation_result
+    pub fn apply_exact_out(
+        ctx: &mut Context<Buy>,
+        walien_amount: u64,
+        max_usdc_in: u64,
+        allowlist_proof: Option<AllowlistProof>,
+    ) -> Result<()> {
+        ctx.accounts
+            .global_config_account
+            .require_sale_open(Clock::get()?.unix_timestamp)?;
+
+        let calculation_result = calculate_exact_out_swap_from_config(
+            &ctx.accounts.global_config_account,
+            ctx.accounts.liquidity_curve.as_deref(),
+            walien_amount,
+        )?;
+        // the curve stops at tick_upper, so the requested output may not be fully available
+        require!(
+            calculation_result.amount_out == walien_amount,
+            ErrorCode::AmountOutBelowMinimum
+        );
+        let usdc_cost = calculation_result
             .amount_in
             .checked_add(calculation_result.fee_amount)
             .ok_or(ErrorCode::AmountCalcOverflow)?;
+        require!(usdc_cost <= max_usdc_in, ErrorCode::TokenMaxExceeded);
+
+        Self::settle(ctx, calculation_result, allowlist_proof)
+    }
+
+    fn settle(
+        ctx: &mut Context<Buy>,
+        calculation_result: SwapStepComputation,
+        allowlist_proof: Option<AllowlistProof>,
+    ) -> Result<()> {
+        let cfg = &mut ctx.accounts.global_config_account;
 
-        let max_usdc_cap = 100_000u64
-            .checked_mul(10u64.pow(USDC_DECIMALS as u32))
+        let transfer_amount = calculation_result
+            .amount_in
+            .checked_add(calculation_result.fee_amount)
             .ok_or(ErrorCode::AmountCalcOverflow)?;
 
-        require!(transfer_amount <= max_usdc_cap, ErrorCode::UsdcCapExceeded);
+        // the fee is tracked in accrued_fees, positions, limits and available_for_swap_in_usdc
+        // only account for the principal
+        let usdc_principal = calculation_result.amount_in;
         require!(
-            cfg.available_for_swap_in_usdc <= max_usdc_cap,
+            !cfg.exceeds_max_purchase(usdc_principal),
             ErrorCode::UsdcCapExceeded
         );
-
         require!(
-            cfg.available_for_swap_in_usdc >= transfer_amount,
+            !cfg.below_min_purchase(usdc_principal),
+            ErrorCode::UsdcBelowMinimum
+        );
+        require!(
+            cfg.available_for_swap_in_usdc >= usdc_principal,
             ErrorCode::InsufficientAvailableForSwap
         );
 
@@ -96,31 +209,64 @@ impl<'info> Buy<'info> {
             calculation_result.amount_out > 0,
             ErrorCode::ZeroTradableAmount
         );
-        require!(
-            calculation_result.amount_out >= min_tokens_out,
-            ErrorCode::SlippageExceeded
-        );
+
+        let wallet_total_usdc = ctx
+            .accounts
+            .user_summary
+            .total_usdc_locked
+            .checked_add(usdc_principal)
+            .ok_or(ErrorCode::AmountCalcOverflow)?;
+        cfg.require_wallet_contribution(wallet_total_usdc)?;
+        cfg.require_allowlisted(
+            &ctx.accounts.user.key(),
+            allowlist_proof.as_ref(),
+            wallet_total_usdc,
+        )?;
 
         let position_index = cfg.possition_index;
         let user_index_position = ctx.accounts.user_summary.index_position;
 
+        // amounts above are in quote units, the pay mint is 1:1 with them
+        let pay_decimals = ctx.accounts.usdc_mint.decimals;
+        let pay_due = to_pay_amount(transfer_amount, pay_decimals, true)?;
+        let vault_balance_before = ctx.accounts.program_usdc_token_account.amount;
+
         {
-            let cpi_accounts = token::Transfer {
+            // the buyer covers any transfer fee of the pay mint on top
+            let send_amount =
+                amount_with_transfer_fee(&ctx.accounts.usdc_mint.to_account_info(), pay_due)?;
+            let cpi_accounts = TransferChecked {
                 from: ctx.accounts.user_usdc_ata.to_account_info(),
                 to: ctx.accounts.program_usdc_token_account.to_account_info(),
                 authority: ctx.accounts.user.to_account_info(),
+                mint: ctx.accounts.usdc_mint.to_account_info(),
             };
             let cpi_program = ctx.accounts.token_program.to_account_info();
             let transfer_ctx = CpiContext::new(cpi_program, cpi_accounts);
 
-            token::transfer(transfer_ctx, transfer_amount)?;
+            token_interface::transfer_checked(transfer_ctx, send_amount, pay_decimals)?;
         }
 
+        // credit what the vault actually received; rounding dust (mints with fewer decimals
+        // than USDC, inverse transfer fee) goes to the fee
+        ctx.accounts.program_usdc_token_account.reload()?;
+        let pay_amount = ctx
+            .accounts
+            .program_usdc_token_account
+            .amount
+            .checked_sub(vault_balance_before)
+            .ok_or(ErrorCode::AmountCalcOverflow)?;
+        require!(pay_amount >= pay_due, ErrorCode::PayTransferShortfall);
+        let pay_fee = pay_amount
+            .checked_sub(to_pay_amount(usdc_principal, pay_decimals, false)?)
+            .ok_or(ErrorCode::AmountCalcOverflow)?;
+
         ctx.accounts.user_account.authority = ctx.accounts.user.key();
+        ctx.accounts.user_account.pay_mint = ctx.accounts.usdc_mint.key();
 
         let now_ts = Clock::get()?.unix_timestamp;
         ctx.accounts.user_account.last_buy_timestamp = now_ts;
-        ctx.accounts.user_account.usdc_spent += transfer_amount;
+        ctx.accounts.user_account.usdc_spent += usdc_principal;
         ctx.accounts.user_account.walien_allocation += calculation_result.amount_out;
 
         ctx.accounts.user_summary.authority = ctx.accounts.user.key();
@@ -129,7 +275,7 @@ impl<'info> Buy<'info> {
             .accounts
             .user_summary
             .total_usdc_locked
-            .checked_add(transfer_amount)
+            .checked_add(usdc_principal)
             .ok_or(ErrorCode::AmountCalcOverflow)?;
         ctx.accounts.user_summary.total_walien_alloc = ctx
             .accounts
@@ -140,8 +286,26 @@ impl<'info> Buy<'info> {
 
         cfg.available_for_swap_in_usdc = cfg
             .available_for_swap_in_usdc
-            .checked_sub(transfer_amount)
+            .checked_sub(usdc_principal)
             .ok_or(ErrorCode::InsufficientAvailableForSwap)?;
+        cfg.total_usdc_raised = cfg
+            .total_usdc_raised
+            .checked_add(usdc_principal)
+            .ok_or(ErrorCode::AmountCalcOverflow)?;
+        match ctx.accounts.pay_mint.as_mut() {
+            Some(pay_mint) => {
+                pay_mint.accrued_fees = pay_mint
+                    .accrued_fees
+                    .checked_add(pay_fee)
+                    .ok_or(ErrorCode::AmountCalcOverflow)?;
+            }
+            None => {
+                cfg.accrued_fees = cfg
+                    .accrued_fees
+                    .checked_add(pay_fee)
+                    .ok_or(ErrorCode::AmountCalcOverflow)?;
+            }
+        }
         cfg.initial_sqrt_price_x64 = calculation_result.next_price;
         ctx.accounts.user_account.index = position_index;
         ctx.accounts.user_summary.global_index_position = position_index;
@@ -151,14 +315,112 @@ impl<'info> Buy<'info> {
         cfg.possition_index += 1;
 
         emit!(BuyEvent {
+            pool_id: cfg.pool_id,
             user: ctx.accounts.user.key(),
             user_position: ctx.accounts.user_account.key(),
             position_index,
             usdc_amount: transfer_amount,
+            fee_amount: calculation_result.fee_amount,
+            pay_mint: ctx.accounts.usdc_mint.key(),
+            pay_amount,
             walien_amount: calculation_result.amount_out,
             price_after: calculation_result.next_price,
         });
 
+        if let Some(referrer) = ctx.accounts.referrer.as_mut() {
+            // USDC rewards are carved out of the fee and paid from the USDC vault, so buys in
+            // other pay mints only earn Walien rewards
+            let referral_fee = if ctx.accounts.pay_mint.is_none() {
+                calculation_result.fee_amount
+            } else {
+                0
+            };
+            let (usdc_reward, walien_reward) =
+                referrer.reward_for(referral_fee, calculation_result.amount_out)?;
+            let walien_reward = cfg.reserve_referral_walien(walien_reward);
+            cfg.accrued_fees = cfg
+                .accrued_fees
+                .checked_sub(usdc_reward)
+                .ok_or(ErrorCode::AmountCalcOverflow)?;
+            referrer.accrued_usdc = referrer
+                .accrued_usdc
+                .checked_add(usdc_reward)
+                .ok_or(ErrorCode::AmountCalcOverflow)?;
+            referrer.accrued_walien = referrer
+                .accrued_walien
+                .checked_add(walien_reward)
+                .ok_or(ErrorCode::AmountCalcOverflow)?;
+            referrer.total_referred_usdc = referrer
+                .total_referred_usdc
+                .checked_add(usdc_principal)
+                .ok_or(ErrorCode::AmountCalcOverflow)?;
+
+            emit!(ReferralEvent {
+                pool_id: cfg.pool_id,
+                referrer: referrer.authority,
+                user: ctx.accounts.user.key(),
+                user_position: ctx.accounts.user_account.key(),
+                usdc_amount: transfer_amount,
+                usdc_reward,
+                walien_reward,
+            });
+        }
+
+        if ctx.accounts.global_config_account.mint_position_receipts {
+            Self::mint_position_receipt(ctx)?;
+        }
+
+        Ok(())
+    }
+
+    fn mint_position_receipt(ctx: &mut Context<Buy>) -> Result<()> {
+        let (
+            Some(receipt_mint),
+            Some(user_receipt_token_account),
+            Some(token_program_2022),
+            Some(associated_token_program),
+            Some(receipt_mint_bump),
+        ) = (
+            ctx.accounts.receipt_mint.as_ref(),
+            ctx.accounts.user_receipt_token_account.as_ref(),
+            ctx.accounts.token_program_2022.as_ref(),
+            ctx.accounts.associated_token_program.as_ref(),
+            ctx.bumps.receipt_mint,
+        )
+        else {
+            return err!(ErrorCode::ReceiptAccountsMissing);
+        };
+
+        let position_key = ctx.accounts.user_account.key();
+        let mint_seeds: &[&[u8]] = &[
+            RECEIPT_MINT_SEED,
+            position_key.as_ref(),
+            &[receipt_mint_bump],
+        ];
+        let pool_id_bytes = ctx.accounts.global_config_account.pool_id.to_le_bytes();
+        let config_seeds: &[&[u8]] = &[
+            CONFIG_SEED,
+            pool_id_bytes.as_ref(),
+            &[ctx.accounts.global_config_account.bump],
+        ];
+        let accounts = ReceiptAccounts {
+            payer: ctx.accounts.user.to_account_info(),
+            owner: ctx.accounts.user.to_account_info(),
+            mint: receipt_mint.to_account_info(),
+            owner_token_account: user_receipt_token_account.to_account_info(),
+            global_config: ctx.accounts.global_config_account.to_account_info(),
+            token_program: token_program_2022.to_account_info(),
+            associated_token_program: associated_token_program.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+        };
+        mint_receipt(
+            &accounts,
+            &ctx.accounts.user_account,
+            mint_seeds,
+            config_seeds,
+        )?;
+
+        ctx.accounts.user_account.receipt_mint = Some(receipt_mint.key());
         Ok(())
     }
 }
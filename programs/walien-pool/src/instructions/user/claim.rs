@@ -1,21 +1,17 @@
 use crate::{
+    claims::{forward_proceeds, walien_ata_exists, PositionClaim, WalienPayout},
     constants::{CONFIG_SEED, USER_SUMMARY_SEED, VAULT_USDC_SEED, VAULT_WALIEN_SEED},
     errors::ErrorCode,
-    events::ClaimEvent,
+    receipt::{burn_receipt, require_receipt_holder},
     state::{GlobalConfig, UserPosition, UserSummary},
 };
 use anchor_lang::prelude::*;
-use anchor_spl::token_2022::spl_token_2022::{
-    self,
-    extension::StateWithExtensions,
-    state::Account as Token2022Account,
-};
-use anchor_spl::token::{self, Token, TokenAccount as SplTokenAccount, Mint as SplMint};
-use anchor_spl::token_2022::{Token2022, TransferChecked, transfer_checked};
+use anchor_spl::token_interface::{TokenInterface, TokenAccount as SplTokenAccount, Mint as SplMint};
+use anchor_spl::token_2022::Token2022;
 use anchor_spl::token_interface::{
     Mint as Token2022Mint, TokenAccount as Token2022TokenAccount,
 };
-use anchor_spl::associated_token::{create, AssociatedToken, Create};
+use anchor_spl::associated_token::AssociatedToken;
 
 #[derive(Accounts)]
 #[instruction(possition_index: u64)]
@@ -23,32 +19,39 @@ pub struct Claim<'info> {
     #[account(
         mut,
         seeds = [
-            CONFIG_SEED
+            CONFIG_SEED,
+            global_config_account.pool_id.to_le_bytes().as_ref()
         ],
-        bump
+        bump = global_config_account.bump
     )]
     pub global_config_account: Account<'info, GlobalConfig>,
     /// CHECK: Admin constrained by address
     #[account(address = global_config_account.admin)]
     pub admin: AccountInfo<'info>,
     #[account(
-        constraint = global_config_account.usdc_mint == usdc_mint.key()
+        constraint = user_account.pay_mint == usdc_mint.key() @ ErrorCode::InvalidPayMint
     )]
-    pub usdc_mint: Account<'info, SplMint>,
+    pub usdc_mint: InterfaceAccount<'info, SplMint>,
     #[account(
         mut,
-        seeds = [VAULT_USDC_SEED],
+        seeds = [
+            VAULT_USDC_SEED,
+            global_config_account.key().as_ref(),
+            global_config_account.pay_vault_seed(&usdc_mint.key())
+        ],
         bump,
         token::mint = usdc_mint,
         token::authority = global_config_account,
+        token::token_program = token_program,
     )]
-    pub program_usdc_token_account: Account<'info, SplTokenAccount>,
+    pub program_usdc_token_account: InterfaceAccount<'info, SplTokenAccount>,
     #[account(
         mut,
         associated_token::mint = usdc_mint,
-        associated_token::authority = admin
+        associated_token::authority = admin,
+        associated_token::token_program = token_program,
     )]
-    pub admin_usdc_token_account: Account<'info, SplTokenAccount>,
+    pub admin_usdc_token_account: InterfaceAccount<'info, SplTokenAccount>,
     #[account(mut)]
     pub caller: Signer<'info>,
 
@@ -67,7 +70,7 @@ pub struct Claim<'info> {
     pub walien_mint: InterfaceAccount<'info, Token2022Mint>,
     #[account(
         mut,
-        seeds = [VAULT_WALIEN_SEED],
+        seeds = [VAULT_WALIEN_SEED, global_config_account.key().as_ref()],
         bump,
         token::mint = walien_mint,
         token::authority = global_config_account,
@@ -76,126 +79,127 @@ pub struct Claim<'info> {
     pub program_walien_token_account: InterfaceAccount<'info, Token2022TokenAccount>,
     #[account(
         mut,
-        seeds = [USER_SUMMARY_SEED, user.key().as_ref()],
+        seeds = [
+            USER_SUMMARY_SEED,
+            global_config_account.key().as_ref(),
+            user_account.authority.as_ref()
+        ],
         bump,
-        constraint = user_summary.authority == user.key()
+        constraint = user_summary.authority == user_account.authority
     )]
     pub user_summary: Account<'info, UserSummary>,
     /// CHECK: We know, that it is walien buyer, constraint = user_account.authority == user.key()
+    /// or, for positions with a receipt, the receipt holder (checked in `apply`)
     #[account(
         mut,
-        constraint = user_account.authority == user.key()
+        constraint = user_account.authority == user.key() || user_account.receipt_mint.is_some()
     )]
     pub user: AccountInfo<'info>,
     /// CHECK: ATA may or may not exist
     #[account(mut)]
     pub user_walien_token_account: UncheckedAccount<'info>,
-    pub token_program: Program<'info, Token>,
+    /// CHECK: Receipt NFT mint of the position, required if it has one
+    #[account(
+        mut,
+        constraint = user_account.receipt_mint == Some(receipt_mint.key()) @ ErrorCode::InvalidReceipt
+    )]
+    pub receipt_mint: Option<UncheckedAccount<'info>>,
+    /// CHECK: `user`'s token account holding the receipt, checked in `apply`
+    #[account(mut)]
+    pub user_receipt_token_account: Option<UncheckedAccount<'info>>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub token_program_2022: Program<'info, Token2022>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
 }
 
 impl<'info> Claim<'info> {
-    pub fn apply(ctx: &mut Context<Claim>, _possition_index: u64) -> Result<()> {
-        require!(
-            ctx.accounts.global_config_account.is_claim_active,
-            ErrorCode::ClaimIsNotActive
-        );
-        require!(
-            ctx.accounts.user_account.walien_allocation > 0,
-            ErrorCode::NothingToClaim
-        );
-        let walien_amount = ctx.accounts.user_account.walien_allocation;
-        let usdc_spent = ctx.accounts.user_account.usdc_spent;
-        let position_index = ctx.accounts.user_account.index;
-        let user_position = ctx.accounts.user_account.key();
-        let ata_already_exists = ctx.accounts.user_walien_token_account.owner == &spl_token_2022::ID
-            && !ctx.accounts.user_walien_token_account.data_is_empty();
-
-        if ata_already_exists {
-            let user_walien_ai = ctx.accounts.user_walien_token_account.to_account_info();
-            let data = user_walien_ai.try_borrow_data()?;
-            
-            let token_account = StateWithExtensions::<Token2022Account>::unpack(&data)?;
-
-            require!(
-                token_account.base.mint == ctx.accounts.walien_mint.key()
-                    && token_account.base.owner == ctx.accounts.user.key(),
-                ErrorCode::InvalidWalienTokenAccount
-            );
-        }
+    pub fn apply(
+        ctx: &mut Context<'_, '_, '_, 'info, Claim<'info>>,
+        _possition_index: u64,
+    ) -> Result<()> {
+        let now_ts = Clock::get()?.unix_timestamp;
+        ctx.accounts.global_config_account.require_claim_open(now_ts)?;
+
+        // with a receipt NFT, whoever holds it owns the position
+        let receipt = match ctx.accounts.user_account.receipt_mint {
+            Some(receipt_mint) => {
+                let (Some(mint), Some(token_account)) = (
+                    ctx.accounts.receipt_mint.as_ref(),
+                    ctx.accounts.user_receipt_token_account.as_ref(),
+                ) else {
+                    return err!(ErrorCode::ReceiptAccountsMissing);
+                };
+                require_receipt_holder(token_account, &receipt_mint, &ctx.accounts.user.key())?;
+                Some((mint.to_account_info(), token_account.to_account_info()))
+            }
+            None => None,
+        };
 
+        let claim = PositionClaim::compute(
+            &ctx.accounts.global_config_account,
+            &ctx.accounts.user_account,
+            now_ts,
+        )?;
+        require!(claim.walien_amount > 0, ErrorCode::NothingToClaim);
+        let ata_already_exists = walien_ata_exists(
+            &ctx.accounts.user_walien_token_account,
+            &ctx.accounts.walien_mint.key(),
+            &ctx.accounts.user.key(),
+        )?;
+
+        let pool_id_bytes = ctx.accounts.global_config_account.pool_id.to_le_bytes();
         let seeds = &[
-            CONFIG_SEED.as_ref(),
+            CONFIG_SEED,
+            pool_id_bytes.as_ref(),
             &[ctx.accounts.global_config_account.bump],
         ];
         let signer = &[&seeds[..]];
 
-        if !ata_already_exists {
-            let cpi_accounts = Create {
-                payer: ctx.accounts.caller.to_account_info(),
-                associated_token: ctx.accounts.user_walien_token_account.to_account_info(),
-                authority: ctx.accounts.user.to_account_info(),
-                mint: ctx.accounts.walien_mint.to_account_info(),
-                system_program: ctx.accounts.system_program.to_account_info(),
-                token_program: ctx.accounts.token_program_2022.to_account_info(),
-            };
-            create(CpiContext::new(
-                ctx.accounts.associated_token_program.to_account_info(),
-                cpi_accounts,
-            ))?;
-        }
-
-        {
-            let cpi_accounts = TransferChecked {
-                from: ctx.accounts.program_walien_token_account.to_account_info(),
-                to: ctx.accounts.user_walien_token_account.to_account_info(),
-                authority: ctx.accounts.global_config_account.to_account_info(),
-                mint: ctx.accounts.walien_mint.to_account_info(),
-            };
-            let cpi_program = ctx.accounts.token_program_2022.to_account_info();
-            let transfer_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
-
-            transfer_checked(transfer_ctx, ctx.accounts.user_account.walien_allocation, ctx.accounts.walien_mint.decimals)?;
-        }
-
-        {
-            let cpi_accounts = token::Transfer {
-                from: ctx.accounts.program_usdc_token_account.to_account_info(),
-                to: ctx.accounts.admin_usdc_token_account.to_account_info(),
-                authority: ctx.accounts.global_config_account.to_account_info(),
-            };
-            let cpi_program = ctx.accounts.token_program.to_account_info();
-            let transfer_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
-
-            token::transfer(transfer_ctx, usdc_spent)?;
-        }
-
-        emit!(ClaimEvent {
-            caller: ctx.accounts.caller.key(),
-            user: ctx.accounts.user.key(),
-            user_position,
-            position_index,
-            walien_amount,
-        });
-
-        ctx.accounts.user_summary.total_walien_alloc = ctx
-            .accounts
-            .user_summary
-            .total_walien_alloc
-            .checked_sub(walien_amount)
-            .ok_or(ErrorCode::UserSummaryUnderflow)?;
-
-        ctx.accounts.user_summary.total_usdc_locked = ctx
-            .accounts
-            .user_summary
-            .total_usdc_locked
-            .checked_sub(usdc_spent)
-            .ok_or(ErrorCode::UserSummaryUnderflow)?;
-
-        let should_close_user_summary = ctx.accounts.user_summary.total_walien_alloc == 0
-            && ctx.accounts.user_summary.total_usdc_locked == 0;
+        let payout = WalienPayout {
+            payer: ctx.accounts.caller.to_account_info(),
+            walien_mint: ctx.accounts.walien_mint.to_account_info(),
+            walien_decimals: ctx.accounts.walien_mint.decimals,
+            vault: ctx.accounts.program_walien_token_account.to_account_info(),
+            global_config: ctx.accounts.global_config_account.to_account_info(),
+            token_program_2022: ctx.accounts.token_program_2022.to_account_info(),
+            associated_token_program: ctx.accounts.associated_token_program.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+            hook_accounts: ctx.remaining_accounts.to_vec(),
+        };
+        payout.pay(
+            ctx.accounts.user.to_account_info(),
+            ctx.accounts.user_walien_token_account.to_account_info(),
+            ata_already_exists,
+            claim.walien_amount,
+            signer,
+        )?;
+        forward_proceeds(
+            &ctx.accounts.usdc_mint,
+            ctx.accounts.program_usdc_token_account.to_account_info(),
+            ctx.accounts.admin_usdc_token_account.to_account_info(),
+            ctx.accounts.global_config_account.to_account_info(),
+            ctx.accounts.token_program.to_account_info(),
+            claim.usdc_amount,
+            signer,
+        )?;
+
+        emit!(claim.event(
+            ctx.accounts.global_config_account.pool_id,
+            ctx.accounts.caller.key(),
+            ctx.accounts.user.key(),
+            ctx.accounts.user_account.key(),
+            &ctx.accounts.user_account,
+        ));
+
+        let fully_claimed = claim.book(
+            &mut ctx.accounts.user_account,
+            &mut ctx.accounts.user_summary,
+        )?;
+
+        // the summary belongs to the buyer, a receipt holder doesn't get its rent
+        let should_close_user_summary = ctx.accounts.user_summary.is_empty()
+            && ctx.accounts.user_summary.authority == ctx.accounts.user.key();
 
         let recipient = if ata_already_exists {
             ctx.accounts.user.to_account_info()
@@ -203,9 +207,19 @@ impl<'info> Claim<'info> {
             ctx.accounts.caller.to_account_info()
         };
 
-        ctx.accounts.user_account.walien_allocation = 0;
-
-        ctx.accounts.user_account.close(recipient.clone())?;
+        if fully_claimed {
+            if let Some((receipt_mint, user_receipt_token_account)) = receipt {
+                burn_receipt(
+                    receipt_mint,
+                    user_receipt_token_account,
+                    ctx.accounts.global_config_account.to_account_info(),
+                    ctx.accounts.token_program_2022.to_account_info(),
+                    recipient.clone(),
+                    signer,
+                )?;
+            }
+            ctx.accounts.user_account.close(recipient.clone())?;
+        }
 
         if should_close_user_summary {
             ctx.accounts.user_summary.close(recipient)?;
@@ -0,0 +1,380 @@
+use crate::{
+    claims::{forward_proceeds, walien_ata_exists, PositionClaim, WalienPayout},
+    constants::{CONFIG_SEED, USER_SUMMARY_SEED, VAULT_USDC_SEED, VAULT_WALIEN_SEED},
+    errors::ErrorCode,
+    state::{GlobalConfig, UserPosition, UserSummary},
+};
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_2022::Token2022;
+use anchor_spl::token_interface::{
+    Mint as SplMint, Mint as Token2022Mint, TokenAccount as SplTokenAccount,
+    TokenAccount as Token2022TokenAccount, TokenInterface,
+};
+
+/// Same accounts as `Claim` minus the position: the positions to claim are passed as the
+/// first `position_indices.len()` writable remaining accounts, in the same order as the
+/// indices. Any further remaining accounts are forwarded to the Walien transfer hook.
+#[derive(Accounts)]
+pub struct ClaimMany<'info> {
+    #[account(
+        mut,
+        seeds = [
+            CONFIG_SEED,
+            global_config_account.pool_id.to_le_bytes().as_ref()
+        ],
+        bump = global_config_account.bump
+    )]
+    pub global_config_account: Account<'info, GlobalConfig>,
+    /// CHECK: Admin constrained by address
+    #[account(address = global_config_account.admin)]
+    pub admin: AccountInfo<'info>,
+    pub usdc_mint: InterfaceAccount<'info, SplMint>,
+    #[account(
+        mut,
+        seeds = [
+            VAULT_USDC_SEED,
+            global_config_account.key().as_ref(),
+            global_config_account.pay_vault_seed(&usdc_mint.key())
+        ],
+        bump,
+        token::mint = usdc_mint,
+        token::authority = global_config_account,
+        token::token_program = token_program,
+    )]
+    pub program_usdc_token_account: InterfaceAccount<'info, SplTokenAccount>,
+    #[account(
+        mut,
+        associated_token::mint = usdc_mint,
+        associated_token::authority = admin,
+        associated_token::token_program = token_program,
+    )]
+    pub admin_usdc_token_account: InterfaceAccount<'info, SplTokenAccount>,
+    #[account(mut)]
+    pub caller: Signer<'info>,
+    #[account(
+        constraint = global_config_account.walien_mint == Some(walien_mint.key())
+    )]
+    pub walien_mint: InterfaceAccount<'info, Token2022Mint>,
+    #[account(
+        mut,
+        seeds = [VAULT_WALIEN_SEED, global_config_account.key().as_ref()],
+        bump,
+        token::mint = walien_mint,
+        token::authority = global_config_account,
+        token::token_program = token_program_2022,
+    )]
+    pub program_walien_token_account: InterfaceAccount<'info, Token2022TokenAccount>,
+    #[account(
+        mut,
+        seeds = [
+            USER_SUMMARY_SEED,
+            global_config_account.key().as_ref(),
+            user.key().as_ref()
+        ],
+        bump,
+        constraint = user_summary.authority == user.key()
+    )]
+    pub user_summary: Account<'info, UserSummary>,
+    /// CHECK: Walien buyer, every position's authority is checked against it
+    #[account(mut)]
+    pub user: AccountInfo<'info>,
+    /// CHECK: ATA may or may not exist
+    #[account(mut)]
+    pub user_walien_token_account: UncheckedAccount<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub token_program_2022: Program<'info, Token2022>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Loads the positions at `position_indices` from `infos`, one account per index. Every
+/// position must be a distinct, writable PDA of its index, owned by `user`, paid in
+/// `usdc_mint` and without a receipt.
+fn load_positions<'info>(
+    position_indices: &[u64],
+    infos: &'info [AccountInfo<'info>],
+    global_config_key: &Pubkey,
+    user: &Pubkey,
+    usdc_mint: &Pubkey,
+) -> Result<Vec<Account<'info, UserPosition>>> {
+    let mut positions = Vec::with_capacity(position_indices.len());
+    for (i, (index, info)) in position_indices.iter().zip(infos).enumerate() {
+        require!(
+            !position_indices[..i].contains(index),
+            ErrorCode::DuplicatePosition
+        );
+        let (expected, _) = Pubkey::find_program_address(
+            &[global_config_key.as_ref(), index.to_le_bytes().as_ref()],
+            &crate::ID,
+        );
+        require_keys_eq!(info.key(), expected, ErrorCode::InvalidPositionAccount);
+        require!(info.is_writable, ErrorCode::InvalidPositionAccount);
+
+        let position = Account::<UserPosition>::try_from(info)?;
+        require_keys_eq!(position.authority, *user, ErrorCode::InvalidPositionAccount);
+        require_keys_eq!(position.pay_mint, *usdc_mint, ErrorCode::InvalidPayMint);
+        require!(
+            position.receipt_mint.is_none(),
+            ErrorCode::PositionHasReceipt
+        );
+        positions.push(position);
+    }
+    Ok(positions)
+}
+
+impl<'info> ClaimMany<'info> {
+    pub fn apply(
+        ctx: &mut Context<'_, '_, 'info, 'info, ClaimMany<'info>>,
+        position_indices: Vec<u64>,
+    ) -> Result<()> {
+        let now_ts = Clock::get()?.unix_timestamp;
+        ctx.accounts
+            .global_config_account
+            .require_claim_open(now_ts)?;
+
+        let count = position_indices.len();
+        require!(count > 0, ErrorCode::NothingToClaim);
+        require!(
+            ctx.remaining_accounts.len() >= count,
+            ErrorCode::InvalidPositionAccount
+        );
+        let (position_infos, hook_accounts) = ctx.remaining_accounts.split_at(count);
+
+        let positions = load_positions(
+            &position_indices,
+            position_infos,
+            &ctx.accounts.global_config_account.key(),
+            &ctx.accounts.user.key(),
+            &ctx.accounts.usdc_mint.key(),
+        )?;
+
+        let mut total_walien_amount: u64 = 0;
+        let mut total_usdc_amount: u64 = 0;
+        let mut claims = Vec::with_capacity(count);
+        for position in positions.iter() {
+            let claim =
+                PositionClaim::compute(&ctx.accounts.global_config_account, position, now_ts)?;
+            total_walien_amount = total_walien_amount
+                .checked_add(claim.walien_amount)
+                .ok_or(ErrorCode::AmountCalcOverflow)?;
+            total_usdc_amount = total_usdc_amount
+                .checked_add(claim.usdc_amount)
+                .ok_or(ErrorCode::AmountCalcOverflow)?;
+            claims.push(claim);
+        }
+        require!(total_walien_amount > 0, ErrorCode::NothingToClaim);
+        let ata_already_exists = walien_ata_exists(
+            &ctx.accounts.user_walien_token_account,
+            &ctx.accounts.walien_mint.key(),
+            &ctx.accounts.user.key(),
+        )?;
+
+        let pool_id_bytes = ctx.accounts.global_config_account.pool_id.to_le_bytes();
+        let seeds = &[
+            CONFIG_SEED,
+            pool_id_bytes.as_ref(),
+            &[ctx.accounts.global_config_account.bump],
+        ];
+        let signer = &[&seeds[..]];
+
+        let payout = WalienPayout {
+            payer: ctx.accounts.caller.to_account_info(),
+            walien_mint: ctx.accounts.walien_mint.to_account_info(),
+            walien_decimals: ctx.accounts.walien_mint.decimals,
+            vault: ctx.accounts.program_walien_token_account.to_account_info(),
+            global_config: ctx.accounts.global_config_account.to_account_info(),
+            token_program_2022: ctx.accounts.token_program_2022.to_account_info(),
+            associated_token_program: ctx.accounts.associated_token_program.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+            hook_accounts: hook_accounts.to_vec(),
+        };
+        payout.pay(
+            ctx.accounts.user.to_account_info(),
+            ctx.accounts.user_walien_token_account.to_account_info(),
+            ata_already_exists,
+            total_walien_amount,
+            signer,
+        )?;
+        forward_proceeds(
+            &ctx.accounts.usdc_mint,
+            ctx.accounts.program_usdc_token_account.to_account_info(),
+            ctx.accounts.admin_usdc_token_account.to_account_info(),
+            ctx.accounts.global_config_account.to_account_info(),
+            ctx.accounts.token_program.to_account_info(),
+            total_usdc_amount,
+            signer,
+        )?;
+
+        let recipient = if ata_already_exists {
+            ctx.accounts.user.to_account_info()
+        } else {
+            ctx.accounts.caller.to_account_info()
+        };
+
+        for (mut position, claim) in positions.into_iter().zip(claims) {
+            if claim.walien_amount == 0 {
+                continue;
+            }
+            emit!(claim.event(
+                ctx.accounts.global_config_account.pool_id,
+                ctx.accounts.caller.key(),
+                ctx.accounts.user.key(),
+                position.key(),
+                &position,
+            ));
+
+            if claim.book(&mut position, &mut ctx.accounts.user_summary)? {
+                position.close(recipient.clone())?;
+            } else {
+                position.exit(ctx.program_id)?;
+            }
+        }
+
+        if ctx.accounts.user_summary.is_empty() {
+            ctx.accounts.user_summary.close(recipient)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Pool {
+        global_config_key: Pubkey,
+        user: Pubkey,
+        usdc_mint: Pubkey,
+    }
+
+    impl Pool {
+        fn new() -> Self {
+            Self {
+                global_config_key: Pubkey::new_unique(),
+                user: Pubkey::new_unique(),
+                usdc_mint: Pubkey::new_unique(),
+            }
+        }
+
+        fn position(&self, index: u64) -> UserPosition {
+            UserPosition {
+                authority: self.user,
+                index,
+                usdc_spent: 300,
+                walien_allocation: 1_000,
+                claimed_amount: 0,
+                last_buy_timestamp: 0,
+                pay_mint: self.usdc_mint,
+                receipt_mint: None,
+            }
+        }
+
+        /// Passes `positions`, each at the PDA of its own index, for `position_indices`.
+        fn load(&self, position_indices: &[u64], positions: &[UserPosition]) -> Result<usize> {
+            let keys: Vec<Pubkey> = positions
+                .iter()
+                .map(|position| {
+                    Pubkey::find_program_address(
+                        &[
+                            self.global_config_key.as_ref(),
+                            position.index.to_le_bytes().as_ref(),
+                        ],
+                        &crate::ID,
+                    )
+                    .0
+                })
+                .collect();
+            let mut data: Vec<Vec<u8>> = positions
+                .iter()
+                .map(|position| {
+                    let mut data = Vec::new();
+                    position.try_serialize(&mut data).unwrap();
+                    data
+                })
+                .collect();
+            let mut lamports = vec![0u64; positions.len()];
+            let infos: Vec<AccountInfo> = keys
+                .iter()
+                .zip(data.iter_mut())
+                .zip(lamports.iter_mut())
+                .map(|((key, data), lamports)| {
+                    AccountInfo::new(key, false, true, lamports, data, &crate::ID, false, 0)
+                })
+                .collect();
+            load_positions(
+                position_indices,
+                &infos,
+                &self.global_config_key,
+                &self.user,
+                &self.usdc_mint,
+            )
+            .map(|positions| positions.len())
+        }
+    }
+
+    #[test]
+    fn test_load_positions_accepts_the_users_positions() {
+        let pool = Pool::new();
+        assert_eq!(
+            pool.load(&[4, 2], &[pool.position(4), pool.position(2)])
+                .unwrap(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_load_positions_rejects_duplicates() {
+        let pool = Pool::new();
+        assert_eq!(
+            pool.load(&[4, 4], &[pool.position(4), pool.position(4)])
+                .unwrap_err(),
+            ErrorCode::DuplicatePosition.into()
+        );
+    }
+
+    #[test]
+    fn test_load_positions_rejects_the_wrong_pda() {
+        let pool = Pool::new();
+        assert_eq!(
+            pool.load(&[4, 2], &[pool.position(4), pool.position(3)])
+                .unwrap_err(),
+            ErrorCode::InvalidPositionAccount.into()
+        );
+    }
+
+    #[test]
+    fn test_load_positions_rejects_another_authority() {
+        let pool = Pool::new();
+        let mut other = pool.position(2);
+        other.authority = Pubkey::new_unique();
+        assert_eq!(
+            pool.load(&[4, 2], &[pool.position(4), other]).unwrap_err(),
+            ErrorCode::InvalidPositionAccount.into()
+        );
+    }
+
+    #[test]
+    fn test_load_positions_rejects_a_mixed_pay_mint() {
+        let pool = Pool::new();
+        let mut other = pool.position(2);
+        other.pay_mint = Pubkey::new_unique();
+        assert_eq!(
+            pool.load(&[4, 2], &[pool.position(4), other]).unwrap_err(),
+            ErrorCode::InvalidPayMint.into()
+        );
+    }
+
+    #[test]
+    fn test_load_positions_rejects_receipt_positions() {
+        let pool = Pool::new();
+        let mut receipt = pool.position(2);
+        receipt.receipt_mint = Some(Pubkey::new_unique());
+        assert_eq!(
+            pool.load(&[4, 2], &[pool.position(4), receipt])
+                .unwrap_err(),
+            ErrorCode::PositionHasReceipt.into()
+        );
+    }
+}
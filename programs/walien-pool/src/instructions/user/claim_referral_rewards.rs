@@ -0,0 +1,158 @@
+use crate::{
+    constants::{CONFIG_SEED, PAUSE_CLAIM, REFERRER_SEED, VAULT_USDC_SEED, VAULT_WALIEN_SEED},
+    errors::ErrorCode,
+    events::ReferralRewardsClaimedEvent,
+    state::{GlobalConfig, Referrer},
+    token_extensions::{amount_with_transfer_fee, transfer_checked_with_hook},
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token_2022::{Token2022, TransferChecked};
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface};
+use anchor_spl::token_interface::{Mint as Token2022Mint, TokenAccount as Token2022TokenAccount};
+
+#[derive(Accounts)]
+pub struct ClaimReferralRewards<'info> {
+    #[account(
+        mut,
+        seeds = [
+            CONFIG_SEED,
+            global_config_account.pool_id.to_le_bytes().as_ref()
+        ],
+        bump = global_config_account.bump
+    )]
+    pub global_config_account: Account<'info, GlobalConfig>,
+    pub authority: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [
+            REFERRER_SEED,
+            global_config_account.key().as_ref(),
+            authority.key().as_ref()
+        ],
+        bump = referrer.bump
+    )]
+    pub referrer: Account<'info, Referrer>,
+    #[account(
+        constraint = global_config_account.usdc_mint == usdc_mint.key()
+    )]
+    pub usdc_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        seeds = [VAULT_USDC_SEED, global_config_account.key().as_ref()],
+        bump,
+        token::mint = usdc_mint,
+        token::authority = global_config_account,
+        token::token_program = token_program,
+    )]
+    pub program_usdc_token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        token::mint = usdc_mint,
+        token::authority = authority,
+        token::token_program = token_program,
+    )]
+    pub authority_usdc_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
+    // Walien rewards are only paid when these are passed and claims are open
+    #[account(
+        constraint = global_config_account.walien_mint == Some(walien_mint.key())
+    )]
+    pub walien_mint: Option<InterfaceAccount<'info, Token2022Mint>>,
+    #[account(
+        mut,
+        seeds = [VAULT_WALIEN_SEED, global_config_account.key().as_ref()],
+        bump,
+        token::mint = walien_mint,
+        token::authority = global_config_account,
+        token::token_program = token_program_2022,
+    )]
+    pub program_walien_token_account: Option<InterfaceAccount<'info, Token2022TokenAccount>>,
+    #[account(
+        mut,
+        token::mint = walien_mint,
+        token::authority = authority,
+        token::token_program = token_program_2022,
+    )]
+    pub authority_walien_token_account: Option<InterfaceAccount<'info, Token2022TokenAccount>>,
+    pub token_program_2022: Option<Program<'info, Token2022>>,
+}
+
+impl<'info> ClaimReferralRewards<'info> {
+    pub fn apply(ctx: &mut Context<'_, '_, '_, 'info, ClaimReferralRewards<'info>>) -> Result<()> {
+        ctx.accounts
+            .global_config_account
+            .require_not_paused(PAUSE_CLAIM)?;
+        let pool_id_bytes = ctx.accounts.global_config_account.pool_id.to_le_bytes();
+        let seeds = &[
+            CONFIG_SEED,
+            pool_id_bytes.as_ref(),
+            &[ctx.accounts.global_config_account.bump],
+        ];
+        let signer = &[&seeds[..]];
+
+        let usdc_amount = ctx.accounts.referrer.accrued_usdc;
+        if usdc_amount > 0 {
+            let cpi_accounts = TransferChecked {
+                from: ctx.accounts.program_usdc_token_account.to_account_info(),
+                to: ctx.accounts.authority_usdc_token_account.to_account_info(),
+                authority: ctx.accounts.global_config_account.to_account_info(),
+                mint: ctx.accounts.usdc_mint.to_account_info(),
+            };
+            let cpi_program = ctx.accounts.token_program.to_account_info();
+            let transfer_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+            token_interface::transfer_checked(
+                transfer_ctx,
+                usdc_amount,
+                ctx.accounts.usdc_mint.decimals,
+            )?;
+            ctx.accounts.referrer.accrued_usdc = 0;
+        }
+
+        let claim_open = ctx
+            .accounts
+            .global_config_account
+            .require_claim_open(Clock::get()?.unix_timestamp)
+            .is_ok();
+        let mut walien_amount = 0;
+        if let (true, Some(walien_mint), Some(from), Some(to), Some(token_program_2022)) = (
+            claim_open,
+            ctx.accounts.walien_mint.as_ref(),
+            ctx.accounts.program_walien_token_account.as_ref(),
+            ctx.accounts.authority_walien_token_account.as_ref(),
+            ctx.accounts.token_program_2022.as_ref(),
+        ) {
+            walien_amount = ctx.accounts.referrer.accrued_walien;
+            if walien_amount > 0 {
+                let cpi_accounts = TransferChecked {
+                    from: from.to_account_info(),
+                    to: to.to_account_info(),
+                    authority: ctx.accounts.global_config_account.to_account_info(),
+                    mint: walien_mint.to_account_info(),
+                };
+                let cpi_program = token_program_2022.to_account_info();
+                let transfer_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer)
+                    .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+                let gross_amount =
+                    amount_with_transfer_fee(&walien_mint.to_account_info(), walien_amount)?;
+                transfer_checked_with_hook(transfer_ctx, gross_amount, walien_mint.decimals)?;
+                ctx.accounts.referrer.accrued_walien = 0;
+                ctx.accounts
+                    .global_config_account
+                    .pay_referral_walien(walien_amount)?;
+            }
+        }
+
+        require!(
+            usdc_amount > 0 || walien_amount > 0,
+            ErrorCode::NothingToClaim
+        );
+
+        emit!(ReferralRewardsClaimedEvent {
+            pool_id: ctx.accounts.global_config_account.pool_id,
+            referrer: ctx.accounts.authority.key(),
+            usdc_amount,
+            walien_amount,
+        });
+        Ok(())
+    }
+}
@@ -0,0 +1,621 @@
+use crate::{
+    claims::{forward_proceeds, walien_ata_exists, PositionClaim, WalienPayout},
+    constants::{CONFIG_SEED, USER_SUMMARY_SEED, VAULT_USDC_SEED, VAULT_WALIEN_SEED},
+    errors::ErrorCode,
+    events::ClaimsCrankedEvent,
+    state::{GlobalConfig, UserPosition, UserSummary},
+    token_extensions::transfer_checked_with_hook,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::{get_associated_token_address_with_program_id, AssociatedToken};
+use anchor_spl::token_2022::spl_token_2022;
+use anchor_spl::token_2022::{Token2022, TransferChecked};
+use anchor_spl::token_interface::{
+    Mint as SplMint, Mint as Token2022Mint, TokenAccount as SplTokenAccount,
+    TokenAccount as Token2022TokenAccount, TokenInterface,
+};
+
+/// Accounts passed per position, as remaining accounts:
+/// `[user_account, user_summary, user, user_walien_token_account]`.
+pub const CRANK_ACCOUNTS_PER_POSITION: usize = 4;
+
+/// Pushes vested Walien of positions `start_index..start_index + count` to their owners.
+/// Remaining accounts hold `count` groups of `CRANK_ACCOUNTS_PER_POSITION` accounts, then the
+/// Walien transfer hook accounts, if any. Positions that don't check out are skipped.
+#[derive(Accounts)]
+pub struct CrankClaims<'info> {
+    #[account(
+        mut,
+        seeds = [
+            CONFIG_SEED,
+            global_config_account.pool_id.to_le_bytes().as_ref()
+        ],
+        bump = global_config_account.bump
+    )]
+    pub global_config_account: Account<'info, GlobalConfig>,
+    /// CHECK: Admin constrained by address
+    #[account(address = global_config_account.admin)]
+    pub admin: AccountInfo<'info>,
+    /// Only positions paid in this mint are processed.
+    pub usdc_mint: InterfaceAccount<'info, SplMint>,
+    #[account(
+        mut,
+        seeds = [
+            VAULT_USDC_SEED,
+            global_config_account.key().as_ref(),
+            global_config_account.pay_vault_seed(&usdc_mint.key())
+        ],
+        bump,
+        token::mint = usdc_mint,
+        token::authority = global_config_account,
+        token::token_program = token_program,
+    )]
+    pub program_usdc_token_account: InterfaceAccount<'info, SplTokenAccount>,
+    #[account(
+        mut,
+        associated_token::mint = usdc_mint,
+        associated_token::authority = admin,
+        associated_token::token_program = token_program,
+    )]
+    pub admin_usdc_token_account: InterfaceAccount<'info, SplTokenAccount>,
+    #[account(mut)]
+    pub caller: Signer<'info>,
+    /// Receives the wALIEN crank reward; without it only the lamport reward is paid.
+    #[account(
+        mut,
+        token::mint = walien_mint,
+        token::authority = caller,
+        token::token_program = token_program_2022,
+    )]
+    pub caller_walien_token_account: Option<InterfaceAccount<'info, Token2022TokenAccount>>,
+    #[account(
+        constraint = global_config_account.walien_mint == Some(walien_mint.key())
+    )]
+    pub walien_mint: InterfaceAccount<'info, Token2022Mint>,
+    #[account(
+        mut,
+        seeds = [VAULT_WALIEN_SEED, global_config_account.key().as_ref()],
+        bump,
+        token::mint = walien_mint,
+        token::authority = global_config_account,
+        token::token_program = token_program_2022,
+    )]
+    pub program_walien_token_account: InterfaceAccount<'info, Token2022TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub token_program_2022: Program<'info, Token2022>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+struct PendingClaim<'info> {
+    position: Account<'info, UserPosition>,
+    user_summary: Account<'info, UserSummary>,
+    user: &'info AccountInfo<'info>,
+    user_walien_token_account: &'info AccountInfo<'info>,
+    ata_already_exists: bool,
+    claim: PositionClaim,
+}
+
+/// Loads and checks the accounts of position `index`; `None` means skip it.
+fn pending_claim<'info>(
+    cfg: &GlobalConfig,
+    global_config_key: &Pubkey,
+    usdc_mint: &Pubkey,
+    walien_mint: &Pubkey,
+    index: u64,
+    group: &'info [AccountInfo<'info>],
+    now_ts: i64,
+) -> Result<Option<PendingClaim<'info>>> {
+    let [position_info, summary_info, user, user_walien_token_account] = group else {
+        return Ok(None);
+    };
+
+    let (expected_position, _) = Pubkey::find_program_address(
+        &[global_config_key.as_ref(), index.to_le_bytes().as_ref()],
+        &crate::ID,
+    );
+    let (expected_summary, _) = Pubkey::find_program_address(
+        &[
+            USER_SUMMARY_SEED,
+            global_config_key.as_ref(),
+            user.key().as_ref(),
+        ],
+        &crate::ID,
+    );
+    let expected_ata =
+        get_associated_token_address_with_program_id(user.key, walien_mint, &spl_token_2022::ID);
+    if position_info.key() != expected_position
+        || summary_info.key() != expected_summary
+        || user_walien_token_account.key() != expected_ata
+        || !position_info.is_writable
+        || !summary_info.is_writable
+        || !user.is_writable
+        || !user_walien_token_account.is_writable
+    {
+        return Ok(None);
+    }
+
+    // closed or never created positions fail to deserialize
+    let Ok(position) = Account::<UserPosition>::try_from(position_info) else {
+        return Ok(None);
+    };
+    let Ok(user_summary) = Account::<UserSummary>::try_from(summary_info) else {
+        return Ok(None);
+    };
+    if position.authority != user.key()
+        || user_summary.authority != user.key()
+        || position.pay_mint != *usdc_mint
+        || position.receipt_mint.is_some()
+    {
+        return Ok(None);
+    }
+
+    // a frozen or foreign ATA would fail the whole batch
+    let Ok(ata_already_exists) =
+        walien_ata_exists(user_walien_token_account, walien_mint, user.key)
+    else {
+        return Ok(None);
+    };
+
+    let claim = PositionClaim::compute(cfg, &position, now_ts)?;
+    if claim.walien_amount == 0 {
+        return Ok(None);
+    }
+
+    Ok(Some(PendingClaim {
+        position,
+        user_summary,
+        user,
+        user_walien_token_account,
+        ata_already_exists,
+        claim,
+    }))
+}
+
+/// Reward for `closed` positions at `per_position` each, capped by what is `available`.
+fn crank_reward(per_position: u64, closed: u8, available: u64) -> u64 {
+    per_position.saturating_mul(closed as u64).min(available)
+}
+
+impl<'info> CrankClaims<'info> {
+    pub fn apply(
+        ctx: &mut Context<'_, '_, 'info, 'info, CrankClaims<'info>>,
+        start_index: u64,
+        count: u8,
+    ) -> Result<()> {
+        let now_ts = Clock::get()?.unix_timestamp;
+        ctx.accounts
+            .global_config_account
+            .require_claim_open(now_ts)?;
+
+        let group_accounts = count as usize * CRANK_ACCOUNTS_PER_POSITION;
+        require!(
+            ctx.remaining_accounts.len() >= group_accounts,
+            ErrorCode::CrankAccountsMissing
+        );
+        let (groups, hook_accounts) = ctx.remaining_accounts.split_at(group_accounts);
+
+        let pool_id_bytes = ctx.accounts.global_config_account.pool_id.to_le_bytes();
+        let bump = ctx.accounts.global_config_account.bump;
+        let seeds = &[CONFIG_SEED, pool_id_bytes.as_ref(), &[bump]];
+        let signer = &[&seeds[..]];
+
+        let payout = WalienPayout {
+            payer: ctx.accounts.caller.to_account_info(),
+            walien_mint: ctx.accounts.walien_mint.to_account_info(),
+            walien_decimals: ctx.accounts.walien_mint.decimals,
+            vault: ctx.accounts.program_walien_token_account.to_account_info(),
+            global_config: ctx.accounts.global_config_account.to_account_info(),
+            token_program_2022: ctx.accounts.token_program_2022.to_account_info(),
+            associated_token_program: ctx.accounts.associated_token_program.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+            hook_accounts: hook_accounts.to_vec(),
+        };
+        let global_config_key = ctx.accounts.global_config_account.key();
+        let usdc_mint_key = ctx.accounts.usdc_mint.key();
+        let walien_mint_key = ctx.accounts.walien_mint.key();
+
+        let mut processed: u8 = 0;
+        // only closing a position earns a reward, partial vesting claims could be cranked every slot
+        let mut closed: u8 = 0;
+        let mut total_usdc_amount: u64 = 0;
+        for (offset, group) in groups.chunks(CRANK_ACCOUNTS_PER_POSITION).enumerate() {
+            let Some(index) = start_index.checked_add(offset as u64) else {
+                break;
+            };
+            let Some(mut pending) = pending_claim(
+                &ctx.accounts.global_config_account,
+                &global_config_key,
+                &usdc_mint_key,
+                &walien_mint_key,
+                index,
+                group,
+                now_ts,
+            )?
+            else {
+                continue;
+            };
+            let claim = pending.claim;
+
+            payout.pay(
+                pending.user.clone(),
+                pending.user_walien_token_account.clone(),
+                pending.ata_already_exists,
+                claim.walien_amount,
+                signer,
+            )?;
+
+            emit!(claim.event(
+                ctx.accounts.global_config_account.pool_id,
+                ctx.accounts.caller.key(),
+                pending.user.key(),
+                pending.position.key(),
+                &pending.position,
+            ));
+
+            let fully_claimed = claim.book(&mut pending.position, &mut pending.user_summary)?;
+
+            let recipient = if pending.ata_already_exists {
+                pending.user.clone()
+            } else {
+                ctx.accounts.caller.to_account_info()
+            };
+
+            if fully_claimed {
+                pending.position.close(recipient.clone())?;
+                closed += 1;
+            } else {
+                pending.position.exit(ctx.program_id)?;
+            }
+
+            // written back right away, the next position may belong to the same user
+            if pending.user_summary.is_empty() {
+                pending.user_summary.close(recipient)?;
+            } else {
+                pending.user_summary.exit(ctx.program_id)?;
+            }
+
+            total_usdc_amount = total_usdc_amount
+                .checked_add(claim.usdc_amount)
+                .ok_or(ErrorCode::AmountCalcOverflow)?;
+            processed += 1;
+        }
+
+        forward_proceeds(
+            &ctx.accounts.usdc_mint,
+            ctx.accounts.program_usdc_token_account.to_account_info(),
+            ctx.accounts.admin_usdc_token_account.to_account_info(),
+            ctx.accounts.global_config_account.to_account_info(),
+            ctx.accounts.token_program.to_account_info(),
+            total_usdc_amount,
+            signer,
+        )?;
+
+        let reward_lamports = Self::pay_lamport_reward(ctx, closed)?;
+        let reward_walien = Self::pay_walien_reward(ctx, closed, hook_accounts, signer)?;
+
+        emit!(ClaimsCrankedEvent {
+            pool_id: ctx.accounts.global_config_account.pool_id,
+            caller: ctx.accounts.caller.key(),
+            start_index,
+            processed,
+            skipped: count - processed,
+            closed,
+            reward_lamports,
+            reward_walien,
+        });
+
+        Ok(())
+    }
+
+    /// Pays up to what the config account holds above rent exemption.
+    fn pay_lamport_reward(
+        ctx: &Context<'_, '_, 'info, 'info, CrankClaims<'info>>,
+        closed: u8,
+    ) -> Result<u64> {
+        let config_info = ctx.accounts.global_config_account.to_account_info();
+        let rent_exempt = Rent::get()?.minimum_balance(config_info.data_len());
+        let surplus = config_info.lamports().saturating_sub(rent_exempt);
+        let reward = crank_reward(
+            ctx.accounts.global_config_account.crank_reward_lamports,
+            closed,
+            surplus,
+        );
+        if reward > 0 {
+            **config_info.try_borrow_mut_lamports()? -= reward;
+            **ctx.accounts.caller.try_borrow_mut_lamports()? += reward;
+        }
+        Ok(reward)
+    }
+
+    /// Pays up to the remaining `crank_walien_budget`, if the caller passed a wALIEN account.
+    fn pay_walien_reward(
+        ctx: &mut Context<'_, '_, 'info, 'info, CrankClaims<'info>>,
+        closed: u8,
+        hook_accounts: &'info [AccountInfo<'info>],
+        signer: &[&[&[u8]]],
+    ) -> Result<u64> {
+        let Some(caller_walien_token_account) = ctx.accounts.caller_walien_token_account.as_ref()
+        else {
+            return Ok(0);
+        };
+        let cfg = &ctx.accounts.global_config_account;
+        let reward = crank_reward(cfg.crank_reward_walien, closed, cfg.crank_walien_budget);
+        if reward == 0 {
+            return Ok(0);
+        }
+
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.program_walien_token_account.to_account_info(),
+            to: caller_walien_token_account.to_account_info(),
+            authority: ctx.accounts.global_config_account.to_account_info(),
+            mint: ctx.accounts.walien_mint.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program_2022.to_account_info();
+        let transfer_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer)
+            .with_remaining_accounts(hook_accounts.to_vec());
+        transfer_checked_with_hook(transfer_ctx, reward, ctx.accounts.walien_mint.decimals)?;
+
+        ctx.accounts.global_config_account.crank_walien_budget -= reward;
+        Ok(reward)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::solana_program::program_pack::Pack;
+    use anchor_spl::token_2022::spl_token_2022::state::{
+        Account as Token2022Account, AccountState,
+    };
+
+    struct Group {
+        cfg: GlobalConfig,
+        global_config_key: Pubkey,
+        usdc_mint: Pubkey,
+        walien_mint: Pubkey,
+        user: Pubkey,
+        index: u64,
+        position_key: Pubkey,
+        /// `None` once the position is closed.
+        position: Option<UserPosition>,
+        ata: Option<AccountState>,
+    }
+
+    impl Group {
+        fn new() -> Self {
+            // all-zero config: no vesting, everything is claimable
+            let cfg = GlobalConfig::try_deserialize_unchecked(&mut &[0u8; GlobalConfig::SIZE][..])
+                .unwrap();
+            let global_config_key = Pubkey::new_unique();
+            let usdc_mint = Pubkey::new_unique();
+            let user = Pubkey::new_unique();
+            let index: u64 = 3;
+            let (position_key, _) = Pubkey::find_program_address(
+                &[global_config_key.as_ref(), index.to_le_bytes().as_ref()],
+                &crate::ID,
+            );
+            Self {
+                cfg,
+                global_config_key,
+                usdc_mint,
+                walien_mint: Pubkey::new_unique(),
+                user,
+                index,
+                position_key,
+                position: Some(UserPosition {
+                    authority: user,
+                    index,
+                    usdc_spent: 300,
+                    walien_allocation: 1_000,
+                    claimed_amount: 0,
+                    last_buy_timestamp: 0,
+                    pay_mint: usdc_mint,
+                    receipt_mint: None,
+                }),
+                ata: None,
+            }
+        }
+
+        /// Walien amount the crank would claim, `None` if it skips the position.
+        fn claimable(&self) -> Option<u64> {
+            let (summary_key, _) = Pubkey::find_program_address(
+                &[
+                    USER_SUMMARY_SEED,
+                    self.global_config_key.as_ref(),
+                    self.user.as_ref(),
+                ],
+                &crate::ID,
+            );
+            let ata_key = get_associated_token_address_with_program_id(
+                &self.user,
+                &self.walien_mint,
+                &spl_token_2022::ID,
+            );
+
+            let mut position_data = Vec::new();
+            if let Some(position) = &self.position {
+                position.try_serialize(&mut position_data).unwrap();
+            }
+            let mut summary_data = Vec::new();
+            UserSummary {
+                authority: self.user,
+                total_usdc_locked: 300,
+                total_walien_alloc: 1_000,
+                last_buy_timestamp: 0,
+                global_index_position: 0,
+                index_position: 0,
+            }
+            .try_serialize(&mut summary_data)
+            .unwrap();
+            let mut ata_data = Vec::new();
+            if let Some(state) = self.ata {
+                ata_data = vec![0u8; Token2022Account::LEN];
+                Token2022Account {
+                    mint: self.walien_mint,
+                    owner: self.user,
+                    state,
+                    ..Default::default()
+                }
+                .pack_into_slice(&mut ata_data);
+            }
+
+            let position_owner = if self.position.is_some() {
+                crate::ID
+            } else {
+                System::id()
+            };
+            let ata_owner = if self.ata.is_some() {
+                spl_token_2022::ID
+            } else {
+                System::id()
+            };
+            let system_id = System::id();
+            let (mut l0, mut l1, mut l2, mut l3) = (0, 0, 0, 0);
+            let mut user_data = Vec::new();
+            let group = vec![
+                AccountInfo::new(
+                    &self.position_key,
+                    false,
+                    true,
+                    &mut l0,
+                    &mut position_data,
+                    &position_owner,
+                    false,
+                    0,
+                ),
+                AccountInfo::new(
+                    &summary_key,
+                    false,
+                    true,
+                    &mut l1,
+                    &mut summary_data,
+                    &crate::ID,
+                    false,
+                    0,
+                ),
+                AccountInfo::new(
+                    &self.user,
+                    false,
+                    true,
+                    &mut l2,
+                    &mut user_data,
+                    &system_id,
+                    false,
+                    0,
+                ),
+                AccountInfo::new(
+                    &ata_key,
+                    false,
+                    true,
+                    &mut l3,
+                    &mut ata_data,
+                    &ata_owner,
+                    false,
+                    0,
+                ),
+            ];
+            pending_claim(
+                &self.cfg,
+                &self.global_config_key,
+                &self.usdc_mint,
+                &self.walien_mint,
+                self.index,
+                &group,
+                0,
+            )
+            .unwrap()
+            .map(|pending| pending.claim.walien_amount)
+        }
+    }
+
+    #[test]
+    fn test_pending_claim_accepts_a_claimable_position() {
+        let mut group = Group::new();
+        assert_eq!(group.claimable(), Some(1_000));
+        group.ata = Some(AccountState::Initialized);
+        assert_eq!(group.claimable(), Some(1_000));
+    }
+
+    #[test]
+    fn test_pending_claim_skips_a_closed_position() {
+        let mut group = Group::new();
+        group.position = None;
+        assert_eq!(group.claimable(), None);
+    }
+
+    #[test]
+    fn test_pending_claim_skips_a_foreign_mint_position() {
+        let mut group = Group::new();
+        group.position.as_mut().unwrap().pay_mint = Pubkey::new_unique();
+        assert_eq!(group.claimable(), None);
+    }
+
+    #[test]
+    fn test_pending_claim_skips_a_mismatched_pda() {
+        let mut group = Group::new();
+        group.index += 1;
+        assert_eq!(group.claimable(), None);
+    }
+
+    #[test]
+    fn test_pending_claim_skips_a_frozen_ata() {
+        let mut group = Group::new();
+        group.ata = Some(AccountState::Frozen);
+        assert_eq!(group.claimable(), None);
+    }
+
+    #[test]
+    fn test_pending_claim_skips_receipt_and_fully_claimed_positions() {
+        let mut group = Group::new();
+        group.position.as_mut().unwrap().receipt_mint = Some(Pubkey::new_unique());
+        assert_eq!(group.claimable(), None);
+
+        let mut group = Group::new();
+        group.position.as_mut().unwrap().claimed_amount = 1_000;
+        assert_eq!(group.claimable(), None);
+    }
+
+    #[test]
+    fn test_crank_reward_is_capped_by_the_budget() {
+        assert_eq!(crank_reward(10, 3, 100), 30);
+        assert_eq!(crank_reward(10, 3, 25), 25);
+        assert_eq!(crank_reward(u64::MAX, 2, 25), 25);
+        assert_eq!(crank_reward(10, 3, 0), 0);
+    }
+
+    #[test]
+    fn test_partial_claims_earn_no_reward() {
+        let mut cfg =
+            GlobalConfig::try_deserialize_unchecked(&mut &[0u8; GlobalConfig::SIZE][..]).unwrap();
+        cfg.vesting_duration_secs = 100;
+        let mut position = UserPosition {
+            authority: Pubkey::new_unique(),
+            index: 0,
+            usdc_spent: 300,
+            walien_allocation: 1_000,
+            claimed_amount: 0,
+            last_buy_timestamp: 0,
+            pay_mint: Pubkey::default(),
+            receipt_mint: None,
+        };
+        let mut summary = UserSummary {
+            authority: position.authority,
+            total_usdc_locked: 300,
+            total_walien_alloc: 1_000,
+            last_buy_timestamp: 0,
+            global_index_position: 0,
+            index_position: 0,
+        };
+
+        // the crank counts a position as closed only once it is fully claimed
+        let claim = PositionClaim::compute(&cfg, &position, 50).unwrap();
+        let closed = claim.book(&mut position, &mut summary).unwrap() as u8;
+        assert_eq!(closed, 0);
+        assert_eq!(crank_reward(10, closed, 100), 0);
+
+        let claim = PositionClaim::compute(&cfg, &position, 100).unwrap();
+        let closed = claim.book(&mut position, &mut summary).unwrap() as u8;
+        assert_eq!(crank_reward(10, closed, 100), 10);
+    }
+}
@@ -0,0 +1,88 @@
+use crate::{
+    constants::CONFIG_SEED,
+    errors::ErrorCode,
+    events::PositionsMergedEvent,
+    state::{GlobalConfig, UserPosition},
+};
+use anchor_lang::prelude::*;
+
+/// The positions folded into `user_account` are passed as the first `source_indices.len()`
+/// writable remaining accounts, in the same order as the indices.
+#[derive(Accounts)]
+#[instruction(possition_index: u64)]
+pub struct MergePositions<'info> {
+    #[account(
+        seeds = [
+            CONFIG_SEED,
+            global_config_account.pool_id.to_le_bytes().as_ref()
+        ],
+        bump = global_config_account.bump
+    )]
+    pub global_config_account: Account<'info, GlobalConfig>,
+    #[account(
+        mut,
+        seeds = [
+            global_config_account.key().as_ref(),
+            possition_index.to_le_bytes().as_ref()
+        ],
+        bump,
+        constraint = user_account.authority == user.key() @ ErrorCode::InvalidPositionAccount
+    )]
+    pub user_account: Account<'info, UserPosition>,
+    #[account(mut)]
+    pub user: Signer<'info>,
+}
+
+impl<'info> MergePositions<'info> {
+    /// `UserSummary` totals are sums over the user's positions, so they are unchanged.
+    pub fn apply(
+        ctx: &mut Context<'_, '_, 'info, 'info, MergePositions<'info>>,
+        possition_index: u64,
+        source_indices: Vec<u64>,
+    ) -> Result<()> {
+        let count = source_indices.len();
+        require!(
+            count > 0 && ctx.remaining_accounts.len() >= count,
+            ErrorCode::InvalidPositionAccount
+        );
+
+        let global_config_key = ctx.accounts.global_config_account.key();
+        for (i, (index, info)) in source_indices
+            .iter()
+            .zip(&ctx.remaining_accounts[..count])
+            .enumerate()
+        {
+            require!(
+                *index != possition_index && !source_indices[..i].contains(index),
+                ErrorCode::DuplicatePosition
+            );
+            let (expected, _) = Pubkey::find_program_address(
+                &[global_config_key.as_ref(), index.to_le_bytes().as_ref()],
+                ctx.program_id,
+            );
+            require_keys_eq!(info.key(), expected, ErrorCode::InvalidPositionAccount);
+            require!(info.is_writable, ErrorCode::InvalidPositionAccount);
+
+            let source = Account::<UserPosition>::try_from(info)?;
+            require_keys_eq!(
+                source.authority,
+                ctx.accounts.user.key(),
+                ErrorCode::InvalidPositionAccount
+            );
+            ctx.accounts.user_account.absorb(&source)?;
+            source.close(ctx.accounts.user.to_account_info())?;
+        }
+
+        emit!(PositionsMergedEvent {
+            pool_id: ctx.accounts.global_config_account.pool_id,
+            user: ctx.accounts.user.key(),
+            user_position: ctx.accounts.user_account.key(),
+            position_index: ctx.accounts.user_account.index,
+            merged_indices: source_indices,
+            usdc_spent: ctx.accounts.user_account.usdc_spent,
+            walien_allocation: ctx.accounts.user_account.walien_allocation,
+        });
+
+        Ok(())
+    }
+}
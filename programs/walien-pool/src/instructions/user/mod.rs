@@ -1,9 +1,19 @@
 pub mod buy;
 pub mod claim;
+pub mod claim_many;
+pub mod claim_referral_rewards;
+pub mod crank_claims;
+pub mod merge_positions;
 pub mod quote;
+pub mod transfer_position;
 pub mod withdraw_usdc;
 
 pub use buy::*;
 pub use claim::*;
+pub use claim_many::*;
+pub use claim_referral_rewards::*;
+pub use crank_claims::*;
+pub use merge_positions::*;
 pub use quote::*;
+pub use transfer_position::*;
 pub use withdraw_usdc::*;
@@ -1,29 +1,114 @@
-use crate::{constants::CONFIG_SEED, state::GlobalConfig, utils::calculate_swap_from_config};
+use crate::{
+    constants::{CONFIG_SEED, LIQUIDITY_CURVE_SEED},
+    errors::ErrorCode,
+    orca_math::{
+        checked_mul_div, sqrt_price_from_tick_index, tick_index_from_sqrt_price,
+        SwapStepComputation, TO_Q64,
+    },
+    state::{GlobalConfig, LiquidityCurve},
+    utils::{calculate_exact_out_swap_from_config, calculate_swap_from_config},
+};
 use anchor_lang::prelude::*;
 
+/// Full breakdown of a prospective `buy`, returned by `quote_detailed`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
+pub struct QuoteDetails {
+    pub amount_in: u64,
+    pub fee_amount: u64,
+    pub amount_out: u64,
+    pub next_sqrt_price_x64: u128,
+    pub tick_after: i32,
+    /// (amount_in + fee_amount) / amount_out in raw units, Q64.64.
+    pub average_price_x64: u128,
+    pub reaches_tick_upper: bool,
+    pub exceeds_available_for_swap: bool,
+    /// `buy` would reject it with `UsdcCapExceeded`.
+    pub exceeds_usdc_cap: bool,
+    /// `buy` would reject it with `UsdcBelowMinimum`.
+    pub below_min_purchase: bool,
+}
+
+impl QuoteDetails {
+    pub fn from_swap(cfg: &GlobalConfig, swap: &SwapStepComputation) -> Result<Self> {
+        let usdc_total = swap
+            .amount_in
+            .checked_add(swap.fee_amount)
+            .ok_or(ErrorCode::AmountCalcOverflow)?;
+        let average_price_x64 = if swap.amount_out == 0 {
+            0
+        } else {
+            checked_mul_div(usdc_total as u128, TO_Q64, swap.amount_out as u128)?
+        };
+
+        Ok(QuoteDetails {
+            amount_in: swap.amount_in,
+            fee_amount: swap.fee_amount,
+            amount_out: swap.amount_out,
+            next_sqrt_price_x64: swap.next_price,
+            tick_after: tick_index_from_sqrt_price(&swap.next_price),
+            average_price_x64,
+            reaches_tick_upper: swap.next_price == sqrt_price_from_tick_index(cfg.tick_upper),
+            exceeds_available_for_swap: swap.amount_in > cfg.available_for_swap_in_usdc,
+            exceeds_usdc_cap: cfg.exceeds_max_purchase(swap.amount_in),
+            below_min_purchase: cfg.below_min_purchase(swap.amount_in),
+        })
+    }
+}
+
 #[derive(Accounts)]
 pub struct Quote<'info> {
     #[account(
         seeds = [
-            CONFIG_SEED
+            CONFIG_SEED,
+            global_config_account.pool_id.to_le_bytes().as_ref()
         ],
-        bump
+        bump = global_config_account.bump
     )]
     pub global_config_account: Account<'info, GlobalConfig>,
+    #[account(
+        seeds = [LIQUIDITY_CURVE_SEED, global_config_account.key().as_ref()],
+        bump = liquidity_curve.bump
+    )]
+    pub liquidity_curve: Option<Account<'info, LiquidityCurve>>,
 }
 
 impl<'info> Quote<'info> {
     pub fn apply(ctx: &Context<Quote>, amount: u64) -> Result<u64> {
         let cfg = &ctx.accounts.global_config_account;
-        let calculation_result = calculate_swap_from_config(cfg, amount)?;
+        let curve = ctx.accounts.liquidity_curve.as_deref();
+        let calculation_result = calculate_swap_from_config(cfg, curve, amount)?;
         Ok(calculation_result.amount_out)
     }
+
+    pub fn apply_detailed(ctx: &Context<Quote>, amount: u64) -> Result<QuoteDetails> {
+        let cfg = &ctx.accounts.global_config_account;
+        let curve = ctx.accounts.liquidity_curve.as_deref();
+        let calculation_result = calculate_swap_from_config(cfg, curve, amount)?;
+        QuoteDetails::from_swap(cfg, &calculation_result)
+    }
+
+    /// USDC (fee included) required to buy exactly `walien_amount`.
+    pub fn apply_exact_out(ctx: &Context<Quote>, walien_amount: u64) -> Result<u64> {
+        let cfg = &ctx.accounts.global_config_account;
+        let curve = ctx.accounts.liquidity_curve.as_deref();
+        let calculation_result = calculate_exact_out_swap_from_config(cfg, curve, walien_amount)?;
+        require!(
+            calculation_result.amount_out == walien_amount,
+            ErrorCode::AmountOutBelowMinimum
+        );
+        Ok(calculation_result
+            .amount_in
+            .checked_add(calculation_result.fee_amount)
+            .ok_or(ErrorCode::AmountCalcOverflow)?)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::constants::{USDC_DECIMALS, WALIEN_DECIMALS};
+    use crate::state::{LiquidityCurve, LiquidityRange};
+    use crate::utils::calculate_refund_price_from_config;
     use crate::{errors::ErrorCode, state::GlobalConfig};
     use libm::pow;
 
@@ -44,18 +129,48 @@ mod tests {
 
         let mut cfg = GlobalConfig {
             admin: Pubkey::default(),
+            pending_admin: None,
+            pool_id: 0,
             usdc_mint: Pubkey::default(),
             walien_mint: None,
             is_sale_active: true,
             is_claim_active: false,
+            is_transfer_active: true,
+            pause_flags: 0,
+            sale_start_ts: 0,
+            sale_end_ts: i64::MAX,
+            claim_start_ts: 0,
+            vesting_cliff_secs: 0,
+            vesting_duration_secs: 0,
+            min_usdc_per_wallet: 0,
+            max_usdc_per_wallet: 0,
+            is_allowlist_phase: false,
+            merkle_root: None,
+            min_usdc_per_buy: 0,
+            max_usdc_per_buy: 0,
             bump: 0,
             liqudity: liqudity,
             // liqudity: safe_liquidity_from_b_only(100_000u64 * 10u64.pow(RECEIVE_DECIMALS as u32) as u64, tick_upper)?,
             initial_sqrt_price_x64: sqrtx64,
+            floor_sqrt_price_x64: 0,
+            crank_reward_lamports: 0,
+            crank_reward_walien: 0,
+            crank_walien_budget: 0,
+            referral_walien_budget: 0,
+            referral_walien_owed: 0,
+            mint_position_receipts: false,
             // initial_sqrt_price_x64: 583635577551101000,
             tick_upper: tick_upper,
+            has_liquidity_curve: false,
             fee_bps: 0, // for simple testing
+            accrued_fees: 0,
+            fee_recipient: Pubkey::default(),
+            launch_fee_rate: 0,
+            fee_decay_secs: 0,
+            fee_decay_steps: 0,
             available_for_swap_in_usdc: 0,
+            soft_cap_usdc: 0,
+            total_usdc_raised: 0,
             possition_index: 1,
         };
         let amount_in: u64 = 1000 * 1000_000;
@@ -63,7 +178,7 @@ mod tests {
         let mut accumulated_input: u64 = 0;
 
         while accumulated_input < target_total_input {
-            let res = super::calculate_swap_from_config(&cfg, amount_in).unwrap();
+            let res = super::calculate_swap_from_config(&cfg, None, amount_in).unwrap();
             cfg.initial_sqrt_price_x64 = res.next_price;
             accumulated_input += res.amount_in;
             total_sold += res.amount_out;
@@ -81,18 +196,48 @@ mod tests {
     fn config_default() -> Result<GlobalConfig> {
         Ok(GlobalConfig {
             admin: Pubkey::default(),
+            pending_admin: None,
+            pool_id: 0,
             usdc_mint: Pubkey::default(),
             walien_mint: None,
             is_sale_active: true,
             is_claim_active: false,
+            is_transfer_active: true,
+            pause_flags: 0,
+            sale_start_ts: 0,
+            sale_end_ts: i64::MAX,
+            claim_start_ts: 0,
+            vesting_cliff_secs: 0,
+            vesting_duration_secs: 0,
+            min_usdc_per_wallet: 0,
+            max_usdc_per_wallet: 0,
+            is_allowlist_phase: false,
+            merkle_root: None,
+            min_usdc_per_buy: 0,
+            max_usdc_per_buy: 0,
             bump: 0,
             liqudity: 100000 * 10u64.pow(WALIEN_DECIMALS as u32) as u128,
             // liqudity: safe_liquidity_from_b_only(100_000u64 * 10u64.pow(RECEIVE_DECIMALS as u32) as u64, tick_upper)?,
             initial_sqrt_price_x64: 583635577511010402034,
+            floor_sqrt_price_x64: 0,
+            crank_reward_lamports: 0,
+            crank_reward_walien: 0,
+            crank_walien_budget: 0,
+            referral_walien_budget: 0,
+            referral_walien_owed: 0,
+            mint_position_receipts: false,
             // initial_sqrt_price_x64: 583635577551101000,
             tick_upper: 0,
+            has_liquidity_curve: false,
             fee_bps: 0, // for simple testing
+            accrued_fees: 0,
+            fee_recipient: Pubkey::default(),
+            launch_fee_rate: 0,
+            fee_decay_secs: 0,
+            fee_decay_steps: 0,
             available_for_swap_in_usdc: 0,
+            soft_cap_usdc: 0,
+            total_usdc_raised: 0,
             possition_index: 1,
         })
     }
@@ -102,7 +247,7 @@ mod tests {
         let cfg = config_default().unwrap();
 
         let amount_in = 1000_000u64; // 1 USDC (6 decimals) – example
-        let res = super::calculate_swap_from_config(&cfg, amount_in).unwrap();
+        let res = super::calculate_swap_from_config(&cfg, None, amount_in).unwrap();
 
         assert!(res.amount_out > 0, "amount_out must be > 0");
         assert!(
@@ -115,9 +260,11 @@ mod tests {
     fn test_price_monotonically_increases_with_input() {
         let mut cfg = config_default().unwrap();
 
-        let res1 = super::calculate_swap_from_config(&cfg, 100_000u64 * 10u64.pow(6)).unwrap();
+        let res1 =
+            super::calculate_swap_from_config(&cfg, None, 100_000u64 * 10u64.pow(6)).unwrap();
         cfg.initial_sqrt_price_x64 = res1.next_price;
-        let res2 = super::calculate_swap_from_config(&cfg, 100__000u64 * 10u64.pow(6)).unwrap();
+        let res2 =
+            super::calculate_swap_from_config(&cfg, None, 100__000u64 * 10u64.pow(6)).unwrap();
 
         assert!(
             res2.next_price < res1.next_price,
@@ -132,7 +279,7 @@ mod tests {
 
         let amount_in = 100_000u64;
 
-        let err = super::calculate_swap_from_config(&cfg, amount_in).unwrap_err();
+        let err = super::calculate_swap_from_config(&cfg, None, amount_in).unwrap_err();
         assert_eq!(err, ErrorCode::LiquidityZero.into());
     }
 
@@ -145,10 +292,10 @@ mod tests {
 
         let amount_in = 1_000_000_000u64; // larger so amount_out is visible even after rounding
 
-        let out_fee = super::calculate_swap_from_config(&cfg_fee, amount_in)
+        let out_fee = super::calculate_swap_from_config(&cfg_fee, None, amount_in)
             .unwrap()
             .amount_out;
-        let out_no_fee = super::calculate_swap_from_config(&cfg_no_fee, amount_in)
+        let out_no_fee = super::calculate_swap_from_config(&cfg_no_fee, None, amount_in)
             .unwrap()
             .amount_out;
 
@@ -162,7 +309,7 @@ mod tests {
     fn test_small_amount_gives_small_price_movement() {
         let cfg = config_default().unwrap();
 
-        let res = super::calculate_swap_from_config(&cfg, 1).unwrap(); // 1 wei of USDC
+        let res = super::calculate_swap_from_config(&cfg, None, 1).unwrap(); // 1 wei of USDC
 
         assert!(
             res.next_price < cfg.initial_sqrt_price_x64,
@@ -174,10 +321,74 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_exact_out_matches_exact_in() {
+        let mut cfg = config_default().unwrap();
+        cfg.tick_upper = -61081;
+        cfg.initial_sqrt_price_x64 = 18446744073709552;
+        cfg.liqudity = 106167919507750;
+        cfg.fee_bps = 3000;
+
+        let walien_amount = 1_000 * 10u64.pow(WALIEN_DECIMALS as u32);
+        let exact_out =
+            super::calculate_exact_out_swap_from_config(&cfg, None, walien_amount).unwrap();
+        assert_eq!(exact_out.amount_out, walien_amount);
+
+        let usdc_cost = exact_out.amount_in + exact_out.fee_amount;
+        let exact_in = super::calculate_swap_from_config(&cfg, None, usdc_cost).unwrap();
+        assert!(
+            exact_in.amount_out >= walien_amount,
+            "Paying the exact-out cost must yield at least the requested amount"
+        );
+    }
+
+    #[test]
+    fn test_quote_details_flags() {
+        let mut cfg = config_default().unwrap();
+        cfg.tick_upper = -61081;
+        cfg.initial_sqrt_price_x64 = 18446744073709552;
+        cfg.liqudity = 106167919507750;
+        cfg.available_for_swap_in_usdc = 50_000 * 10u64.pow(USDC_DECIMALS as u32);
+        cfg.min_usdc_per_buy = 10 * 10u64.pow(USDC_DECIMALS as u32);
+        cfg.max_usdc_per_buy = 100_000 * 10u64.pow(USDC_DECIMALS as u32);
+
+        let amount = 1_000 * 10u64.pow(USDC_DECIMALS as u32);
+        let swap = super::calculate_swap_from_config(&cfg, None, amount).unwrap();
+        let details = QuoteDetails::from_swap(&cfg, &swap).unwrap();
+        assert_eq!(details.amount_out, swap.amount_out);
+        assert!(details.tick_after > tick_index_from_sqrt_price(&cfg.initial_sqrt_price_x64));
+        assert!(details.average_price_x64 > 0);
+        assert!(!details.reaches_tick_upper);
+        assert!(!details.exceeds_available_for_swap);
+        assert!(!details.exceeds_usdc_cap);
+        assert!(!details.below_min_purchase);
+
+        let swap = super::calculate_swap_from_config(&cfg, None, u64::MAX / 2).unwrap();
+        let details = QuoteDetails::from_swap(&cfg, &swap).unwrap();
+        assert!(details.reaches_tick_upper);
+        assert_eq!(details.tick_after, cfg.tick_upper);
+        assert!(details.exceeds_available_for_swap);
+        assert!(details.exceeds_usdc_cap);
+
+        let swap = super::calculate_swap_from_config(&cfg, None, 1_000_000).unwrap();
+        let details = QuoteDetails::from_swap(&cfg, &swap).unwrap();
+        assert!(details.below_min_purchase);
+
+        // the bounds apply to the principal, like the wallet caps, not to principal plus fee
+        cfg.fee_bps = 10_000;
+        let swap = super::calculate_swap_from_config(&cfg, None, amount).unwrap();
+        cfg.max_usdc_per_buy = swap.amount_in;
+        cfg.min_usdc_per_buy = swap.amount_in;
+        let details = QuoteDetails::from_swap(&cfg, &swap).unwrap();
+        assert!(swap.fee_amount > 0);
+        assert!(!details.exceeds_usdc_cap);
+        assert!(!details.below_min_purchase);
+    }
+
     #[test]
     fn test_zero_input_error() {
         let cfg = config_default().unwrap();
-        let err = super::calculate_swap_from_config(&cfg, 0).unwrap_err();
+        let err = super::calculate_swap_from_config(&cfg, None, 0).unwrap_err();
         assert_eq!(err, ErrorCode::ZeroTradableAmount.into());
     }
 
@@ -196,7 +407,7 @@ mod tests {
             .map(|amount| {
                 println!("price: {}", cfg.initial_sqrt_price_x64);
                 println!("AMOUNT: {}", amount);
-                let res = super::calculate_swap_from_config(&cfg, *amount).unwrap();
+                let res = super::calculate_swap_from_config(&cfg, None, *amount).unwrap();
                 cfg.initial_sqrt_price_x64 = res.next_price;
                 println!(
                     "Spent: {} AMOUNT OUT raw (receive units): {}",
@@ -225,4 +436,206 @@ mod tests {
             );
         }
     }
+
+    fn refund_config() -> GlobalConfig {
+        let mut cfg = config_default().unwrap();
+        cfg.tick_upper = -61081;
+        cfg.initial_sqrt_price_x64 = 18446744073709552;
+        cfg.floor_sqrt_price_x64 = cfg.initial_sqrt_price_x64;
+        cfg.liqudity = 106167919507750;
+        cfg.fee_bps = 3000;
+        cfg.available_for_swap_in_usdc = 50_000 * 10u64.pow(USDC_DECIMALS as u32);
+        cfg
+    }
+
+    /// Applies a buy the way `Buy::settle` does, returns the wALIEN allocation.
+    fn simulate_buy(cfg: &mut GlobalConfig, usdc_amount: u64) -> u64 {
+        let res = calculate_swap_from_config(cfg, None, usdc_amount).unwrap();
+        cfg.initial_sqrt_price_x64 = res.next_price;
+        cfg.available_for_swap_in_usdc -= res.amount_in;
+        res.amount_out
+    }
+
+    /// Applies a refund the way `WithdrawUSDC::apply` does.
+    fn simulate_refund(cfg: &mut GlobalConfig, usdc_spent: u64, walien_allocation: u64) {
+        cfg.available_for_swap_in_usdc += usdc_spent;
+        cfg.initial_sqrt_price_x64 =
+            calculate_refund_price_from_config(cfg, None, walien_allocation).unwrap();
+    }
+
+    #[test]
+    fn test_buy_then_refund_restores_pool() {
+        let mut cfg = refund_config();
+        let before = cfg.clone();
+
+        let usdc_amount = 1_000 * 10u64.pow(USDC_DECIMALS as u32);
+        let usdc_spent = calculate_swap_from_config(&cfg, None, usdc_amount)
+            .unwrap()
+            .amount_in;
+        let walien = simulate_buy(&mut cfg, usdc_amount);
+        assert!(cfg.initial_sqrt_price_x64 > before.initial_sqrt_price_x64);
+
+        simulate_refund(&mut cfg, usdc_spent, walien);
+        assert_eq!(
+            cfg.available_for_swap_in_usdc,
+            before.available_for_swap_in_usdc
+        );
+        assert!(cfg.initial_sqrt_price_x64 >= before.initial_sqrt_price_x64);
+        assert!(cfg.initial_sqrt_price_x64 - before.initial_sqrt_price_x64 <= 2);
+    }
+
+    #[test]
+    fn test_refund_order_does_not_matter() {
+        let mut cfg = refund_config();
+        let before = cfg.initial_sqrt_price_x64;
+
+        let positions: Vec<(u64, u64)> = [300u64, 1_200, 50, 4_000]
+            .iter()
+            .map(|usdc| {
+                let amount = usdc * 10u64.pow(USDC_DECIMALS as u32);
+                let spent = calculate_swap_from_config(&cfg, None, amount)
+                    .unwrap()
+                    .amount_in;
+                (spent, simulate_buy(&mut cfg, amount))
+            })
+            .collect();
+        let after_buys = cfg.clone();
+
+        let mut in_order = after_buys.clone();
+        for (spent, walien) in positions.iter() {
+            simulate_refund(&mut in_order, *spent, *walien);
+        }
+        let mut reversed = after_buys.clone();
+        for (spent, walien) in positions.iter().rev() {
+            simulate_refund(&mut reversed, *spent, *walien);
+        }
+
+        let diff = in_order
+            .initial_sqrt_price_x64
+            .abs_diff(reversed.initial_sqrt_price_x64);
+        assert!(diff <= positions.len() as u128);
+        for cfg in [&in_order, &reversed] {
+            assert!(cfg.initial_sqrt_price_x64 >= before);
+            assert!(cfg.initial_sqrt_price_x64 - before <= 2 * positions.len() as u128);
+            assert_eq!(
+                cfg.available_for_swap_in_usdc,
+                refund_config().available_for_swap_in_usdc
+            );
+        }
+
+        // A partial refund only gives back that position's share of the move
+        let mut partial = after_buys.clone();
+        let (spent, walien) = positions[3];
+        simulate_refund(&mut partial, spent, walien);
+        assert!(partial.initial_sqrt_price_x64 < after_buys.initial_sqrt_price_x64);
+        assert!(partial.initial_sqrt_price_x64 > before);
+    }
+
+    #[test]
+    fn test_refund_never_goes_below_floor() {
+        let mut cfg = refund_config();
+        let walien = simulate_buy(&mut cfg, 10 * 10u64.pow(USDC_DECIMALS as u32));
+
+        let price = calculate_refund_price_from_config(&cfg, None, walien * 1_000).unwrap();
+        assert_eq!(price, cfg.floor_sqrt_price_x64);
+
+        let at_floor = refund_config();
+        assert_eq!(
+            calculate_refund_price_from_config(&at_floor, None, walien).unwrap(),
+            at_floor.floor_sqrt_price_x64
+        );
+    }
+
+    fn staged_curve(cfg: &mut GlobalConfig, upper_liquidity: u128) -> LiquidityCurve {
+        cfg.has_liquidity_curve = true;
+        LiquidityCurve {
+            global_config: Pubkey::default(),
+            ranges: vec![
+                LiquidityRange {
+                    tick_upper: -137800,
+                    liquidity: cfg.liqudity,
+                },
+                LiquidityRange {
+                    tick_upper: cfg.tick_upper,
+                    liquidity: upper_liquidity,
+                },
+            ],
+            bump: 0,
+        }
+    }
+
+    #[test]
+    fn test_split_range_matches_single_range() {
+        let single = refund_config();
+        let mut cfg = refund_config();
+        let curve = staged_curve(&mut cfg, single.liqudity);
+
+        let amount = 5_000 * 10u64.pow(USDC_DECIMALS as u32);
+        let one = calculate_swap_from_config(&single, None, amount).unwrap();
+        let split = calculate_swap_from_config(&cfg, Some(&curve), amount).unwrap();
+
+        assert!(split.next_price > sqrt_price_from_tick_index(-137800));
+        // each step rounds the fee up, so splitting costs about a raw USDC unit per range
+        assert_eq!(
+            one.amount_in + one.fee_amount,
+            split.amount_in + split.fee_amount
+        );
+        assert!(one.amount_in - split.amount_in <= 1);
+        let walien_per_usdc_unit = one.amount_out / one.amount_in;
+        assert!(one.amount_out - split.amount_out <= 2 * walien_per_usdc_unit);
+        assert!(one.next_price - split.next_price <= one.next_price / 1_000_000_000);
+    }
+
+    #[test]
+    fn test_swap_crosses_into_deeper_range() {
+        let thin = refund_config();
+        let mut cfg = refund_config();
+        let curve = staged_curve(&mut cfg, thin.liqudity * 10);
+
+        let amount = 5_000 * 10u64.pow(USDC_DECIMALS as u32);
+        let one = calculate_swap_from_config(&thin, None, amount).unwrap();
+        let staged = calculate_swap_from_config(&cfg, Some(&curve), amount).unwrap();
+
+        // the same USDC moves the price less and buys more once liquidity deepens
+        assert_eq!(staged.amount_in + staged.fee_amount, amount);
+        assert!(staged.next_price > sqrt_price_from_tick_index(-137800));
+        assert!(staged.next_price < one.next_price);
+        assert!(staged.amount_out > one.amount_out);
+
+        let exact_out =
+            calculate_exact_out_swap_from_config(&cfg, Some(&curve), staged.amount_out).unwrap();
+        assert_eq!(exact_out.amount_out, staged.amount_out);
+        assert!(exact_out.amount_in.abs_diff(staged.amount_in) <= 2);
+    }
+
+    #[test]
+    fn test_refund_across_ranges_restores_price() {
+        let mut cfg = refund_config();
+        let deep_liquidity = cfg.liqudity * 10;
+        let curve = staged_curve(&mut cfg, deep_liquidity);
+        let before = cfg.initial_sqrt_price_x64;
+
+        let amount = 5_000 * 10u64.pow(USDC_DECIMALS as u32);
+        let res = calculate_swap_from_config(&cfg, Some(&curve), amount).unwrap();
+        cfg.initial_sqrt_price_x64 = res.next_price;
+        assert!(cfg.initial_sqrt_price_x64 > sqrt_price_from_tick_index(-137800));
+
+        let price = calculate_refund_price_from_config(&cfg, Some(&curve), res.amount_out).unwrap();
+        assert!(price >= before);
+        assert!(price - before <= 4);
+    }
+
+    #[test]
+    fn test_curve_account_must_match_config() {
+        let mut cfg = refund_config();
+        let liquidity = cfg.liqudity;
+        let curve = staged_curve(&mut cfg, liquidity);
+
+        let err = calculate_swap_from_config(&cfg, None, 1_000_000).unwrap_err();
+        assert_eq!(err, ErrorCode::LiquidityCurveMismatch.into());
+
+        cfg.has_liquidity_curve = false;
+        let err = calculate_swap_from_config(&cfg, Some(&curve), 1_000_000).unwrap_err();
+        assert_eq!(err, ErrorCode::LiquidityCurveMismatch.into());
+    }
 }
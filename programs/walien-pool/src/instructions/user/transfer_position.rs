@@ -0,0 +1,98 @@
+use crate::{
+    constants::{CONFIG_SEED, PAUSE_TRANSFER, USER_SUMMARY_SEED},
+    errors::ErrorCode,
+    events::PositionTransferredEvent,
+    state::{GlobalConfig, UserPosition, UserSummary},
+};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(possition_index: u64)]
+pub struct TransferPosition<'info> {
+    #[account(
+        seeds = [
+            CONFIG_SEED,
+            global_config_account.pool_id.to_le_bytes().as_ref()
+        ],
+        bump = global_config_account.bump
+    )]
+    pub global_config_account: Account<'info, GlobalConfig>,
+    #[account(
+        mut,
+        seeds = [
+            global_config_account.key().as_ref(),
+            possition_index.to_le_bytes().as_ref()
+        ],
+        bump,
+        constraint = user_account.authority == user.key() @ ErrorCode::InvalidPositionAccount,
+        constraint = user_account.receipt_mint.is_none() @ ErrorCode::PositionHasReceipt
+    )]
+    pub user_account: Account<'info, UserPosition>,
+    #[account(
+        mut,
+        seeds = [
+            USER_SUMMARY_SEED,
+            global_config_account.key().as_ref(),
+            user.key().as_ref()
+        ],
+        bump,
+        constraint = user_summary.authority == user.key()
+    )]
+    pub user_summary: Account<'info, UserSummary>,
+    #[account(
+        init_if_needed,
+        space = UserSummary::SIZE,
+        payer = user,
+        seeds = [
+            USER_SUMMARY_SEED,
+            global_config_account.key().as_ref(),
+            new_owner.key().as_ref()
+        ],
+        bump
+    )]
+    pub new_owner_summary: Account<'info, UserSummary>,
+    /// CHECK: Any wallet, becomes the position's authority
+    #[account(
+        constraint = new_owner.key() != user.key() @ ErrorCode::SameOwner
+    )]
+    pub new_owner: AccountInfo<'info>,
+    #[account(mut)]
+    pub user: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> TransferPosition<'info> {
+    /// Only once the sale window is over, so that moving positions around cannot be used to
+    /// get past the per-wallet and allowlist caps.
+    pub fn apply(ctx: &mut Context<TransferPosition>, _possition_index: u64) -> Result<()> {
+        let cfg = &ctx.accounts.global_config_account;
+        cfg.require_not_paused(PAUSE_TRANSFER)?;
+        require!(cfg.is_transfer_active, ErrorCode::TransferIsNotActive);
+
+        let (walien_amount, usdc_amount) = ctx.accounts.user_summary.move_position(
+            &mut ctx.accounts.new_owner_summary,
+            ctx.accounts.new_owner.key(),
+            &ctx.accounts.user_account,
+        )?;
+
+        ctx.accounts.user_account.authority = ctx.accounts.new_owner.key();
+
+        emit!(PositionTransferredEvent {
+            pool_id: ctx.accounts.global_config_account.pool_id,
+            user_position: ctx.accounts.user_account.key(),
+            position_index: ctx.accounts.user_account.index,
+            from: ctx.accounts.user.key(),
+            to: ctx.accounts.new_owner.key(),
+            walien_amount,
+            usdc_amount,
+        });
+
+        if ctx.accounts.user_summary.is_empty() {
+            ctx.accounts
+                .user_summary
+                .close(ctx.accounts.user.to_account_info())?;
+        }
+
+        Ok(())
+    }
+}
@@ -1,13 +1,19 @@
 use crate::{
-    constants::{CONFIG_SEED, USER_SUMMARY_SEED, VAULT_USDC_SEED, VAULT_WALIEN_SEED},
+    constants::{
+        CONFIG_SEED, LIQUIDITY_CURVE_SEED, PAUSE_REFUND, USER_SUMMARY_SEED, VAULT_USDC_SEED,
+        VAULT_WALIEN_SEED,
+    },
     errors::ErrorCode,
     events::ClaimEvent,
-    state::{GlobalConfig, UserPosition, UserSummary},
+    receipt::{burn_receipt, require_receipt_holder},
+    state::{GlobalConfig, LiquidityCurve, UserPosition, UserSummary},
+    utils::{calculate_refund_price_from_config, to_pay_amount},
 };
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token::{self, Mint, Token, TokenAccount},
+    token_2022::Token2022,
+    token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked},
 };
 
 #[derive(Accounts)]
@@ -16,9 +22,10 @@ pub struct WithdrawUSDC<'info> {
     #[account(
         mut,
         seeds = [
-            CONFIG_SEED
+            CONFIG_SEED,
+            global_config_account.pool_id.to_le_bytes().as_ref()
         ],
-        bump
+        bump = global_config_account.bump
     )]
     pub global_config_account: Account<'info, GlobalConfig>,
     #[account(mut)]
@@ -34,46 +41,101 @@ pub struct WithdrawUSDC<'info> {
     )]
     #[account(
         constraint = caller.key() == user_account.authority
+            || user_account.receipt_mint.is_some()
     )]
     pub user_account: Account<'info, UserPosition>,
     #[account(
-        constraint = global_config_account.usdc_mint == usdc_mint.key()
+        constraint = user_account.pay_mint == usdc_mint.key() @ ErrorCode::InvalidPayMint
     )]
-    pub usdc_mint: Account<'info, Mint>,
+    pub usdc_mint: InterfaceAccount<'info, Mint>,
     #[account(
         mut,
 
         associated_token::mint = usdc_mint,
-        associated_token::authority = caller
+        associated_token::authority = caller,
+        associated_token::token_program = token_program,
     )]
-    pub user_usdc_ata: Account<'info, TokenAccount>,
+    pub user_usdc_ata: InterfaceAccount<'info, TokenAccount>,
 
     #[account(
         mut,
-        seeds = [VAULT_USDC_SEED],
+        seeds = [
+            VAULT_USDC_SEED,
+            global_config_account.key().as_ref(),
+            global_config_account.pay_vault_seed(&usdc_mint.key())
+        ],
         bump,
         token::mint = usdc_mint,
         token::authority = global_config_account,
+        token::token_program = token_program,
     )]
-    pub program_usdc_token_account: Account<'info, TokenAccount>,
+    pub program_usdc_token_account: InterfaceAccount<'info, TokenAccount>,
     #[account(
         mut,
-        seeds = [USER_SUMMARY_SEED, caller.key().as_ref()],
+        seeds = [
+            USER_SUMMARY_SEED,
+            global_config_account.key().as_ref(),
+            user_account.authority.as_ref()
+        ],
         bump,
-        constraint = user_summary.authority == caller.key()
+        constraint = user_summary.authority == user_account.authority
     )]
     pub user_summary: Account<'info, UserSummary>,
-    pub token_program: Program<'info, Token>,
+    #[account(
+        seeds = [LIQUIDITY_CURVE_SEED, global_config_account.key().as_ref()],
+        bump = liquidity_curve.bump
+    )]
+    pub liquidity_curve: Option<Account<'info, LiquidityCurve>>,
+    /// CHECK: Receipt NFT mint of the position, required if it has one
+    #[account(
+        mut,
+        constraint = user_account.receipt_mint == Some(receipt_mint.key()) @ ErrorCode::InvalidReceipt
+    )]
+    pub receipt_mint: Option<UncheckedAccount<'info>>,
+    /// CHECK: caller's token account holding the receipt, checked in `apply`
+    #[account(mut)]
+    pub caller_receipt_token_account: Option<UncheckedAccount<'info>>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub token_program_2022: Option<Program<'info, Token2022>>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
 }
 
 impl<'info> WithdrawUSDC<'info> {
     pub fn apply(ctx: &mut Context<WithdrawUSDC>, _possition_index: u64) -> Result<()> {
+        ctx.accounts
+            .global_config_account
+            .require_not_paused(PAUSE_REFUND)?;
+        // Refunds are only open once the sale ended below the soft cap
+        let now_ts = Clock::get()?.unix_timestamp;
         require!(
-            ctx.accounts.program_usdc_token_account.amount > 0,
+            ctx.accounts.global_config_account.is_sale_failed(now_ts),
             ErrorCode::WithdrawNotAllowed
         );
+        require!(
+            ctx.accounts.user_account.claimed_amount == 0,
+            ErrorCode::PositionPartiallyClaimed
+        );
+
+        // with a receipt NFT, whoever holds it owns the position
+        let receipt = match ctx.accounts.user_account.receipt_mint {
+            Some(receipt_mint) => {
+                let (Some(mint), Some(token_account), Some(token_program_2022)) = (
+                    ctx.accounts.receipt_mint.as_ref(),
+                    ctx.accounts.caller_receipt_token_account.as_ref(),
+                    ctx.accounts.token_program_2022.as_ref(),
+                ) else {
+                    return err!(ErrorCode::ReceiptAccountsMissing);
+                };
+                require_receipt_holder(token_account, &receipt_mint, &ctx.accounts.caller.key())?;
+                Some((
+                    mint.to_account_info(),
+                    token_account.to_account_info(),
+                    token_program_2022.to_account_info(),
+                ))
+            }
+            None => None,
+        };
 
         let usdc_amount = ctx.accounts.user_account.usdc_spent;
         let walien_amount = ctx.accounts.user_account.walien_allocation;
@@ -81,6 +143,18 @@ impl<'info> WithdrawUSDC<'info> {
         ctx.accounts
             .global_config_account
             .available_for_swap_in_usdc += usdc_amount;
+        ctx.accounts.global_config_account.initial_sqrt_price_x64 =
+            calculate_refund_price_from_config(
+                &ctx.accounts.global_config_account,
+                ctx.accounts.liquidity_curve.as_deref(),
+                walien_amount,
+            )?;
+        ctx.accounts.global_config_account.total_usdc_raised = ctx
+            .accounts
+            .global_config_account
+            .total_usdc_raised
+            .checked_sub(usdc_amount)
+            .ok_or(ErrorCode::AmountCalcOverflow)?;
 
         ctx.accounts.user_summary.total_usdc_locked = ctx
             .accounts
@@ -95,23 +169,44 @@ impl<'info> WithdrawUSDC<'info> {
             .checked_sub(walien_amount)
             .ok_or(ErrorCode::UserSummaryUnderflow)?;
 
+        // the summary belongs to the buyer, a receipt holder doesn't get its rent
         let should_close_user_summary = ctx.accounts.user_summary.total_usdc_locked == 0
-            && ctx.accounts.user_summary.total_walien_alloc == 0;
+            && ctx.accounts.user_summary.total_walien_alloc == 0
+            && ctx.accounts.user_summary.authority == ctx.accounts.caller.key();
 
-        let cpi_accounts = token::Transfer {
+        let cpi_accounts = TransferChecked {
             from: ctx.accounts.program_usdc_token_account.to_account_info(),
             to: ctx.accounts.user_usdc_ata.to_account_info(),
             authority: ctx.accounts.global_config_account.to_account_info(),
+            mint: ctx.accounts.usdc_mint.to_account_info(),
         };
+        let pool_id_bytes = ctx.accounts.global_config_account.pool_id.to_le_bytes();
         let seeds = &[
             CONFIG_SEED.as_ref(),
+            pool_id_bytes.as_ref(),
             &[ctx.accounts.global_config_account.bump],
         ];
         let signer = &[&seeds[..]];
         let cpi_program = ctx.accounts.token_program.to_account_info();
         let transfer_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
 
-        token::transfer(transfer_ctx, ctx.accounts.user_account.usdc_spent)?;
+        let pay_amount = to_pay_amount(usdc_amount, ctx.accounts.usdc_mint.decimals, false)?;
+        token_interface::transfer_checked(
+            transfer_ctx,
+            pay_amount,
+            ctx.accounts.usdc_mint.decimals,
+        )?;
+
+        if let Some((receipt_mint, caller_receipt_token_account, token_program_2022)) = receipt {
+            burn_receipt(
+                receipt_mint,
+                caller_receipt_token_account,
+                ctx.accounts.global_config_account.to_account_info(),
+                token_program_2022,
+                ctx.accounts.caller.to_account_info(),
+                signer,
+            )?;
+        }
 
         ctx.accounts
             .user_account
@@ -1,13 +1,18 @@
+pub mod claims;
 pub mod constants;
 pub mod errors;
 mod events;
 pub mod instructions;
+pub mod merkle;
 pub mod orca_math;
+pub mod receipt;
 pub mod state;
+pub mod token_extensions;
 pub mod utils;
 
 use anchor_lang::prelude::*;
 use instructions::*;
+use state::{AllowlistProof, LiquidityRange, ReferralRewardKind};
 
 #[cfg(not(feature = "no-entrypoint"))]
 use solana_security_txt::security_txt;
@@ -28,21 +33,42 @@ declare_id!("HEBAUSMDyqBGbEr74ywsctAJJYiJpWarNWgRCyGgkffN");
 pub mod walien_pool {
     use super::*;
 
-    pub fn initialize(
-        mut ctx: Context<Initialize>,
+    pub fn initialize_registry(mut ctx: Context<InitializeRegistry>) -> Result<()> {
+        InitializeRegistry::apply(&mut ctx)
+    }
+
+    pub fn list_pools(ctx: Context<ListPools>) -> Result<Vec<u64>> {
+        ListPools::apply(&ctx)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn initialize_pool(
+        mut ctx: Context<InitializePool>,
+        pool_id: u64,
         initial_sqrt_price_x64: u128,
         tick_upper: i32,
         available_for_swap_in_usdc: u64,
         liquidity: u128,
         fee_bps: u16,
+        min_usdc_per_buy: u64,
+        max_usdc_per_buy: u64,
+        sale_start_ts: i64,
+        sale_end_ts: i64,
+        claim_start_ts: i64,
     ) -> Result<()> {
-        Initialize::apply(
+        InitializePool::apply(
             &mut ctx,
+            pool_id,
             initial_sqrt_price_x64,
             tick_upper,
             available_for_swap_in_usdc,
             liquidity,
             fee_bps,
+            min_usdc_per_buy,
+            max_usdc_per_buy,
+            sale_start_ts,
+            sale_end_ts,
+            claim_start_ts,
         )
     }
 
@@ -50,7 +76,10 @@ pub mod walien_pool {
         SetWalien::apply(&mut ctx)
     }
 
-    pub fn deposit_walien(mut ctx: Context<DepositWalien>, amount: u64) -> Result<()> {
+    pub fn deposit_walien<'info>(
+        mut ctx: Context<'_, '_, '_, 'info, DepositWalien<'info>>,
+        amount: u64,
+    ) -> Result<()> {
         DepositWalien::apply(&mut ctx, amount)
     }
     pub fn set_sale_activity(mut ctx: Context<SetSaleActivity>, is_active: bool) -> Result<()> {
@@ -61,21 +90,250 @@ pub mod walien_pool {
         SetClaimActivity::apply(&mut ctx, is_active)
     }
 
-    pub fn transfer_admin_authority(mut ctx: Context<TransferAdminAuthority>) -> Result<()> {
-        TransferAdminAuthority::apply(&mut ctx)
+    pub fn set_transfer_activity(
+        mut ctx: Context<SetTransferActivity>,
+        is_active: bool,
+    ) -> Result<()> {
+        SetTransferActivity::apply(&mut ctx, is_active)
+    }
+
+    pub fn set_pause_flags(mut ctx: Context<SetPauseFlags>, pause_flags: u8) -> Result<()> {
+        SetPauseFlags::apply(&mut ctx, pause_flags)
+    }
+
+    pub fn set_schedule(
+        mut ctx: Context<SetSchedule>,
+        sale_start_ts: i64,
+        sale_end_ts: i64,
+        claim_start_ts: i64,
+    ) -> Result<()> {
+        SetSchedule::apply(&mut ctx, sale_start_ts, sale_end_ts, claim_start_ts)
+    }
+
+    pub fn set_soft_cap(mut ctx: Context<SetSoftCap>, soft_cap_usdc: u64) -> Result<()> {
+        SetSoftCap::apply(&mut ctx, soft_cap_usdc)
+    }
+
+    pub fn set_crank_reward<'info>(
+        mut ctx: Context<'_, '_, '_, 'info, SetCrankReward<'info>>,
+        crank_reward_lamports: u64,
+        crank_reward_walien: u64,
+        budget_deposit: u64,
+    ) -> Result<()> {
+        SetCrankReward::apply(
+            &mut ctx,
+            crank_reward_lamports,
+            crank_reward_walien,
+            budget_deposit,
+        )
+    }
+
+    pub fn set_vesting(
+        mut ctx: Context<SetVesting>,
+        vesting_cliff_secs: i64,
+        vesting_duration_secs: i64,
+    ) -> Result<()> {
+        SetVesting::apply(&mut ctx, vesting_cliff_secs, vesting_duration_secs)
+    }
+
+    pub fn update_pool_params(
+        mut ctx: Context<UpdatePoolParams>,
+        initial_sqrt_price_x64: u128,
+        tick_upper: i32,
+        available_for_swap_in_usdc: u64,
+        liquidity: u128,
+        fee_bps: u16,
+    ) -> Result<()> {
+        UpdatePoolParams::apply(
+            &mut ctx,
+            initial_sqrt_price_x64,
+            tick_upper,
+            available_for_swap_in_usdc,
+            liquidity,
+            fee_bps,
+        )
+    }
+
+    pub fn set_liquidity_curve(
+        mut ctx: Context<SetLiquidityCurve>,
+        ranges: Vec<LiquidityRange>,
+    ) -> Result<()> {
+        SetLiquidityCurve::apply(&mut ctx, ranges)
+    }
+
+    pub fn set_fee_recipient(mut ctx: Context<SetFeeRecipient>) -> Result<()> {
+        SetFeeRecipient::apply(&mut ctx)
+    }
+
+    pub fn set_fee_schedule(
+        mut ctx: Context<SetFeeSchedule>,
+        launch_fee_rate: u16,
+        fee_decay_secs: i64,
+        fee_decay_steps: u16,
+    ) -> Result<()> {
+        SetFeeSchedule::apply(&mut ctx, launch_fee_rate, fee_decay_secs, fee_decay_steps)
+    }
+
+    pub fn collect_fees(mut ctx: Context<CollectFees>) -> Result<()> {
+        CollectFees::apply(&mut ctx)
+    }
+
+    pub fn register_pay_mint(mut ctx: Context<RegisterPayMint>) -> Result<()> {
+        RegisterPayMint::apply(&mut ctx)
+    }
+
+    pub fn register_referrer(
+        mut ctx: Context<RegisterReferrer>,
+        reward_kind: ReferralRewardKind,
+        reward_rate_bps: u16,
+    ) -> Result<()> {
+        RegisterReferrer::apply(&mut ctx, reward_kind, reward_rate_bps)
+    }
+
+    pub fn fund_referral_budget<'info>(
+        mut ctx: Context<'_, '_, '_, 'info, FundReferralBudget<'info>>,
+        amount: u64,
+    ) -> Result<()> {
+        FundReferralBudget::apply(&mut ctx, amount)
+    }
+
+    pub fn set_referrer_reward(
+        mut ctx: Context<SetReferrerReward>,
+        reward_kind: ReferralRewardKind,
+        reward_rate_bps: u16,
+    ) -> Result<()> {
+        SetReferrerReward::apply(&mut ctx, reward_kind, reward_rate_bps)
+    }
+
+    pub fn set_purchase_limits(
+        mut ctx: Context<SetPurchaseLimits>,
+        min_usdc_per_buy: u64,
+        max_usdc_per_buy: u64,
+    ) -> Result<()> {
+        SetPurchaseLimits::apply(&mut ctx, min_usdc_per_buy, max_usdc_per_buy)
+    }
+
+    pub fn set_wallet_caps(
+        mut ctx: Context<SetWalletCaps>,
+        min_usdc_per_wallet: u64,
+        max_usdc_per_wallet: u64,
+    ) -> Result<()> {
+        SetWalletCaps::apply(&mut ctx, min_usdc_per_wallet, max_usdc_per_wallet)
+    }
+
+    pub fn set_position_receipts(
+        mut ctx: Context<SetPositionReceipts>,
+        mint_position_receipts: bool,
+    ) -> Result<()> {
+        SetPositionReceipts::apply(&mut ctx, mint_position_receipts)
+    }
+
+    pub fn set_merkle_root(
+        mut ctx: Context<SetMerkleRoot>,
+        merkle_root: Option<[u8; 32]>,
+    ) -> Result<()> {
+        SetMerkleRoot::apply(&mut ctx, merkle_root)
+    }
+
+    pub fn set_allowlist_phase(
+        mut ctx: Context<SetAllowlistPhase>,
+        is_allowlist_phase: bool,
+    ) -> Result<()> {
+        SetAllowlistPhase::apply(&mut ctx, is_allowlist_phase)
+    }
+
+    pub fn propose_admin(mut ctx: Context<ProposeAdmin>) -> Result<()> {
+        ProposeAdmin::apply(&mut ctx)
+    }
+
+    pub fn accept_admin(mut ctx: Context<AcceptAdmin>) -> Result<()> {
+        AcceptAdmin::apply(&mut ctx)
+    }
+
+    pub fn cancel_admin_proposal(mut ctx: Context<CancelAdminProposal>) -> Result<()> {
+        CancelAdminProposal::apply(&mut ctx)
+    }
+
+    pub fn propose_registry_admin(mut ctx: Context<ProposeRegistryAdmin>) -> Result<()> {
+        ProposeRegistryAdmin::apply(&mut ctx)
+    }
+
+    pub fn accept_registry_admin(mut ctx: Context<AcceptRegistryAdmin>) -> Result<()> {
+        AcceptRegistryAdmin::apply(&mut ctx)
     }
 
     pub fn quote(ctx: Context<Quote>, amount: u64) -> Result<u64> {
         Quote::apply(&ctx, amount)
     }
-    pub fn buy(mut ctx: Context<Buy>, amount: u64, min_tokens_out: u64) -> Result<()> {
-        Buy::apply(&mut ctx, amount, min_tokens_out)
+    pub fn buy(
+        mut ctx: Context<Buy>,
+        amount: u64,
+        min_tokens_out: u64,
+        allowlist_proof: Option<AllowlistProof>,
+    ) -> Result<()> {
+        Buy::apply(&mut ctx, amount, min_tokens_out, allowlist_proof)
     }
 
-    pub fn claim(mut ctx: Context<Claim>, possition_index: u64) -> Result<()> {
+    pub fn quote_detailed(ctx: Context<Quote>, amount: u64) -> Result<QuoteDetails> {
+        Quote::apply_detailed(&ctx, amount)
+    }
+
+    pub fn quote_exact_out(ctx: Context<Quote>, walien_amount: u64) -> Result<u64> {
+        Quote::apply_exact_out(&ctx, walien_amount)
+    }
+
+    pub fn buy_exact_out(
+        mut ctx: Context<Buy>,
+        walien_amount: u64,
+        max_usdc_in: u64,
+        allowlist_proof: Option<AllowlistProof>,
+    ) -> Result<()> {
+        Buy::apply_exact_out(&mut ctx, walien_amount, max_usdc_in, allowlist_proof)
+    }
+
+    pub fn claim<'info>(
+        mut ctx: Context<'_, '_, '_, 'info, Claim<'info>>,
+        possition_index: u64,
+    ) -> Result<()> {
         Claim::apply(&mut ctx, possition_index)
     }
 
+    pub fn claim_many<'info>(
+        mut ctx: Context<'_, '_, 'info, 'info, ClaimMany<'info>>,
+        position_indices: Vec<u64>,
+    ) -> Result<()> {
+        ClaimMany::apply(&mut ctx, position_indices)
+    }
+
+    pub fn claim_referral_rewards<'info>(
+        mut ctx: Context<'_, '_, '_, 'info, ClaimReferralRewards<'info>>,
+    ) -> Result<()> {
+        ClaimReferralRewards::apply(&mut ctx)
+    }
+
+    pub fn crank_claims<'info>(
+        mut ctx: Context<'_, '_, 'info, 'info, CrankClaims<'info>>,
+        start_index: u64,
+        count: u8,
+    ) -> Result<()> {
+        CrankClaims::apply(&mut ctx, start_index, count)
+    }
+
+    pub fn merge_positions<'info>(
+        mut ctx: Context<'_, '_, 'info, 'info, MergePositions<'info>>,
+        possition_index: u64,
+        source_indices: Vec<u64>,
+    ) -> Result<()> {
+        MergePositions::apply(&mut ctx, possition_index, source_indices)
+    }
+
+    pub fn transfer_position(
+        mut ctx: Context<TransferPosition>,
+        possition_index: u64,
+    ) -> Result<()> {
+        TransferPosition::apply(&mut ctx, possition_index)
+    }
+
     pub fn withdraw_usdc(mut ctx: Context<WithdrawUSDC>, possition_index: u64) -> Result<()> {
         WithdrawUSDC::apply(&mut ctx, possition_index)
     }
@@ -87,7 +345,9 @@ pub mod walien_pool {
         RollbackPosition::apply(&mut ctx, possition_index)
     }
 
-    pub fn withdraw_walien(mut ctx: Context<WithdrawWalien>) -> Result<()> {
+    pub fn withdraw_walien<'info>(
+        mut ctx: Context<'_, '_, '_, 'info, WithdrawWalien<'info>>,
+    ) -> Result<()> {
         WithdrawWalien::apply(&mut ctx)
     }
 }
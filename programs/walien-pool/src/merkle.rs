@@ -0,0 +1,122 @@
+//! Allowlist merkle tree shared by the program, tests and off-chain tooling.
+//!
+//! Leaves are `sha256(0x00 || wallet || cap_le)` and nodes are `sha256(0x01 || min || max)`,
+//! so proofs don't carry left/right flags and a node can't be passed off as a leaf.
+
+use anchor_lang::prelude::Pubkey;
+use solana_sha256_hasher::hashv;
+
+pub type MerkleHash = [u8; 32];
+
+const LEAF_PREFIX: &[u8] = &[0];
+const NODE_PREFIX: &[u8] = &[1];
+
+/// `cap` is the max total USDC the wallet may contribute during the allowlist phase, 0 = no cap.
+pub fn leaf_hash(wallet: &Pubkey, cap: u64) -> MerkleHash {
+    hashv(&[LEAF_PREFIX, wallet.as_ref(), &cap.to_le_bytes()]).to_bytes()
+}
+
+pub fn node_hash(a: &MerkleHash, b: &MerkleHash) -> MerkleHash {
+    let (lo, hi) = if a <= b { (a, b) } else { (b, a) };
+    hashv(&[NODE_PREFIX, lo, hi]).to_bytes()
+}
+
+pub fn verify_proof(proof: &[MerkleHash], root: &MerkleHash, leaf: &MerkleHash) -> bool {
+    let computed = proof
+        .iter()
+        .fold(*leaf, |acc, sibling| node_hash(&acc, sibling));
+    computed == *root
+}
+
+/// Levels from the leaves up to the root. An odd node is carried to the next level as is.
+pub struct MerkleTree {
+    levels: Vec<Vec<MerkleHash>>,
+}
+
+impl MerkleTree {
+    pub fn new(leaves: Vec<MerkleHash>) -> Self {
+        let mut levels = vec![leaves];
+        while levels.last().is_some_and(|level| level.len() > 1) {
+            let next = levels
+                .last()
+                .unwrap()
+                .chunks(2)
+                .map(|pair| match pair {
+                    [a, b] => node_hash(a, b),
+                    [a] => *a,
+                    _ => unreachable!(),
+                })
+                .collect();
+            levels.push(next);
+        }
+        MerkleTree { levels }
+    }
+
+    pub fn from_allowlist(entries: &[(Pubkey, u64)]) -> Self {
+        Self::new(
+            entries
+                .iter()
+                .map(|(wallet, cap)| leaf_hash(wallet, *cap))
+                .collect(),
+        )
+    }
+
+    pub fn root(&self) -> Option<MerkleHash> {
+        self.levels.last().and_then(|level| level.first()).copied()
+    }
+
+    pub fn proof(&self, mut index: usize) -> Option<Vec<MerkleHash>> {
+        if index >= self.levels[0].len() {
+            return None;
+        }
+        let mut proof = Vec::new();
+        for level in &self.levels[..self.levels.len() - 1] {
+            if let Some(sibling) = level.get(index ^ 1) {
+                proof.push(*sibling);
+            }
+            index /= 2;
+        }
+        Some(proof)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn allowlist(n: usize) -> Vec<(Pubkey, u64)> {
+        (0..n)
+            .map(|i| (Pubkey::new_unique(), i as u64 * 1_000_000))
+            .collect()
+    }
+
+    #[test]
+    fn test_every_leaf_verifies() {
+        for n in [1, 2, 3, 5, 8, 13] {
+            let entries = allowlist(n);
+            let tree = MerkleTree::from_allowlist(&entries);
+            let root = tree.root().unwrap();
+            for (i, (wallet, cap)) in entries.iter().enumerate() {
+                let proof = tree.proof(i).unwrap();
+                assert!(verify_proof(&proof, &root, &leaf_hash(wallet, *cap)));
+            }
+        }
+    }
+
+    #[test]
+    fn test_wrong_cap_or_wallet_is_rejected() {
+        let entries = allowlist(5);
+        let tree = MerkleTree::from_allowlist(&entries);
+        let root = tree.root().unwrap();
+        let (wallet, cap) = entries[2];
+        let proof = tree.proof(2).unwrap();
+
+        assert!(!verify_proof(&proof, &root, &leaf_hash(&wallet, cap + 1)));
+        assert!(!verify_proof(
+            &proof,
+            &root,
+            &leaf_hash(&Pubkey::new_unique(), cap)
+        ));
+        assert!(tree.proof(5).is_none());
+    }
+}
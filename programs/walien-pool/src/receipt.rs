@@ -0,0 +1,384 @@
+//! Receipt NFTs for positions, minted by `buy` when `GlobalConfig::mint_position_receipts` is on.
+//!
+//! A receipt is a Token-2022 mint at `[RECEIPT_MINT_SEED, position]` with a supply of one and
+//! its metadata stored in the mint itself. The config PDA is its permanent delegate and close
+//! authority, so the program can burn the receipt and close the mint when the position closes,
+//! whoever holds it at that point.
+
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{self, CreateAccount};
+use anchor_spl::associated_token::{self, Create};
+use anchor_spl::token_2022::{
+    self,
+    spl_token_2022::{
+        self,
+        extension::{ExtensionType, StateWithExtensions},
+        instruction::AuthorityType,
+        state::{Account as Token2022Account, Mint},
+    },
+    Burn, CloseAccount, InitializeMint2, MintTo, SetAuthority,
+};
+use anchor_spl::token_interface::{
+    metadata_pointer_initialize, mint_close_authority_initialize, permanent_delegate_initialize,
+    spl_pod::optional_keys::OptionalNonZeroPubkey,
+    spl_token_metadata_interface::state::{Field, TokenMetadata},
+    token_metadata_initialize, token_metadata_update_field, MetadataPointerInitialize,
+    MintCloseAuthorityInitialize, PermanentDelegateInitialize, TokenMetadataInitialize,
+    TokenMetadataUpdateField,
+};
+
+use crate::{errors::ErrorCode, state::UserPosition};
+
+pub const RECEIPT_SYMBOL: &str = "wALIEN-POS";
+
+const RECEIPT_EXTENSIONS: [ExtensionType; 3] = [
+    ExtensionType::MetadataPointer,
+    ExtensionType::PermanentDelegate,
+    ExtensionType::MintCloseAuthority,
+];
+
+/// Accounts `mint_receipt` needs; `mint` and `owner_token_account` don't exist yet.
+pub struct ReceiptAccounts<'info> {
+    pub payer: AccountInfo<'info>,
+    pub owner: AccountInfo<'info>,
+    pub mint: AccountInfo<'info>,
+    pub owner_token_account: AccountInfo<'info>,
+    pub global_config: AccountInfo<'info>,
+    pub token_program: AccountInfo<'info>,
+    pub associated_token_program: AccountInfo<'info>,
+    pub system_program: AccountInfo<'info>,
+}
+
+/// Metadata of `position`'s receipt: allocation and USDC spent as additional fields.
+pub fn receipt_metadata(
+    position: &UserPosition,
+    mint: Pubkey,
+    update_authority: Pubkey,
+) -> Result<TokenMetadata> {
+    Ok(TokenMetadata {
+        update_authority: OptionalNonZeroPubkey::try_from(Some(update_authority))?,
+        mint,
+        name: format!("wALIEN position #{}", position.index),
+        symbol: RECEIPT_SYMBOL.to_string(),
+        uri: String::new(),
+        additional_metadata: vec![
+            (
+                "walien_allocation".to_string(),
+                position.walien_allocation.to_string(),
+            ),
+            ("usdc_spent".to_string(), position.usdc_spent.to_string()),
+        ],
+    })
+}
+
+/// Creates the receipt mint for `position` and mints it to the owner's ATA. The mint
+/// authority is dropped afterwards, so the supply stays at one.
+pub fn mint_receipt<'info>(
+    accounts: &ReceiptAccounts<'info>,
+    position: &UserPosition,
+    mint_seeds: &[&[u8]],
+    config_seeds: &[&[u8]],
+) -> Result<()> {
+    let config_key = accounts.global_config.key();
+    let metadata = receipt_metadata(position, accounts.mint.key(), config_key)?;
+    let space = ExtensionType::try_calculate_account_len::<Mint>(&RECEIPT_EXTENSIONS)?;
+    // token metadata reallocs the mint, so fund it for the final size up front
+    let lamports = Rent::get()?.minimum_balance(space + metadata.tlv_size_of()?);
+    let token_program_id = accounts.token_program.key();
+    let config_signer = &[config_seeds];
+
+    system_program::create_account(
+        CpiContext::new_with_signer(
+            accounts.system_program.clone(),
+            CreateAccount {
+                from: accounts.payer.clone(),
+                to: accounts.mint.clone(),
+            },
+            &[mint_seeds],
+        ),
+        lamports,
+        space as u64,
+        &token_program_id,
+    )?;
+
+    permanent_delegate_initialize(
+        CpiContext::new(
+            accounts.token_program.clone(),
+            PermanentDelegateInitialize {
+                token_program_id: accounts.token_program.clone(),
+                mint: accounts.mint.clone(),
+            },
+        ),
+        &config_key,
+    )?;
+    mint_close_authority_initialize(
+        CpiContext::new(
+            accounts.token_program.clone(),
+            MintCloseAuthorityInitialize {
+                token_program_id: accounts.token_program.clone(),
+                mint: accounts.mint.clone(),
+            },
+        ),
+        Some(&config_key),
+    )?;
+    metadata_pointer_initialize(
+        CpiContext::new(
+            accounts.token_program.clone(),
+            MetadataPointerInitialize {
+                token_program_id: accounts.token_program.clone(),
+                mint: accounts.mint.clone(),
+            },
+        ),
+        Some(config_key),
+        Some(accounts.mint.key()),
+    )?;
+    token_2022::initialize_mint2(
+        CpiContext::new(
+            accounts.token_program.clone(),
+            InitializeMint2 {
+                mint: accounts.mint.clone(),
+            },
+        ),
+        0,
+        &config_key,
+        None,
+    )?;
+
+    token_metadata_initialize(
+        CpiContext::new_with_signer(
+            accounts.token_program.clone(),
+            TokenMetadataInitialize {
+                program_id: accounts.token_program.clone(),
+                metadata: accounts.mint.clone(),
+                update_authority: accounts.global_config.clone(),
+                mint_authority: accounts.global_config.clone(),
+                mint: accounts.mint.clone(),
+            },
+            config_signer,
+        ),
+        metadata.name,
+        metadata.symbol,
+        metadata.uri,
+    )?;
+    for (key, value) in metadata.additional_metadata {
+        token_metadata_update_field(
+            CpiContext::new_with_signer(
+                accounts.token_program.clone(),
+                TokenMetadataUpdateField {
+                    program_id: accounts.token_program.clone(),
+                    metadata: accounts.mint.clone(),
+                    update_authority: accounts.global_config.clone(),
+                },
+                config_signer,
+            ),
+            Field::Key(key),
+            value,
+        )?;
+    }
+
+    associated_token::create_idempotent(CpiContext::new(
+        accounts.associated_token_program.clone(),
+        Create {
+            payer: accounts.payer.clone(),
+            associated_token: accounts.owner_token_account.clone(),
+            authority: accounts.owner.clone(),
+            mint: accounts.mint.clone(),
+            system_program: accounts.system_program.clone(),
+            token_program: accounts.token_program.clone(),
+        },
+    ))?;
+    token_2022::mint_to(
+        CpiContext::new_with_signer(
+            accounts.token_program.clone(),
+            MintTo {
+                mint: accounts.mint.clone(),
+                to: accounts.owner_token_account.clone(),
+                authority: accounts.global_config.clone(),
+            },
+            config_signer,
+        ),
+        1,
+    )?;
+    token_2022::set_authority(
+        CpiContext::new_with_signer(
+            accounts.token_program.clone(),
+            SetAuthority {
+                current_authority: accounts.global_config.clone(),
+                account_or_mint: accounts.mint.clone(),
+            },
+            config_signer,
+        ),
+        AuthorityType::MintTokens,
+        None,
+    )?;
+
+    Ok(())
+}
+
+/// Checks that `token_account` is a Token-2022 account of `mint` owned by `holder` with the
+/// receipt in it.
+pub fn require_receipt_holder(
+    token_account: &AccountInfo,
+    mint: &Pubkey,
+    holder: &Pubkey,
+) -> Result<()> {
+    require_keys_eq!(
+        *token_account.owner,
+        spl_token_2022::ID,
+        ErrorCode::InvalidReceipt
+    );
+    let data = token_account.try_borrow_data()?;
+    let token_account = StateWithExtensions::<Token2022Account>::unpack(&data)
+        .map_err(|_| ErrorCode::InvalidReceipt)?;
+    require!(
+        token_account.base.mint == *mint
+            && token_account.base.owner == *holder
+            && token_account.base.amount == 1,
+        ErrorCode::InvalidReceipt
+    );
+    Ok(())
+}
+
+/// Burns the receipt out of `holder_token_account` as permanent delegate and closes its mint,
+/// sending the mint's rent to `destination`.
+pub fn burn_receipt<'info>(
+    mint: AccountInfo<'info>,
+    holder_token_account: AccountInfo<'info>,
+    global_config: AccountInfo<'info>,
+    token_program: AccountInfo<'info>,
+    destination: AccountInfo<'info>,
+    config_signer: &[&[&[u8]]],
+) -> Result<()> {
+    token_2022::burn(
+        CpiContext::new_with_signer(
+            token_program.clone(),
+            Burn {
+                mint: mint.clone(),
+                from: holder_token_account,
+                authority: global_config.clone(),
+            },
+            config_signer,
+        ),
+        1,
+    )?;
+    token_2022::close_account(CpiContext::new_with_signer(
+        token_program,
+        CloseAccount {
+            account: mint,
+            destination,
+            authority: global_config,
+        },
+        config_signer,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::solana_program::program_pack::Pack;
+    use anchor_spl::token_2022::spl_token_2022::state::AccountState;
+
+    fn position() -> UserPosition {
+        UserPosition {
+            authority: Pubkey::new_unique(),
+            index: 42,
+            usdc_spent: 1_500_000,
+            walien_allocation: 7_000_000_000,
+            claimed_amount: 0,
+            last_buy_timestamp: 0,
+            pay_mint: Pubkey::default(),
+            receipt_mint: None,
+        }
+    }
+
+    fn token_account(mint: Pubkey, owner: Pubkey, amount: u64) -> Vec<u8> {
+        let mut data = vec![0u8; Token2022Account::LEN];
+        Token2022Account {
+            mint,
+            owner,
+            amount,
+            state: AccountState::Initialized,
+            ..Default::default()
+        }
+        .pack_into_slice(&mut data);
+        data
+    }
+
+    #[test]
+    fn test_receipt_metadata_carries_allocation_and_usdc_spent() {
+        let mint = Pubkey::new_unique();
+        let config = Pubkey::new_unique();
+        let metadata = receipt_metadata(&position(), mint, config).unwrap();
+        assert_eq!(metadata.mint, mint);
+        assert_eq!(
+            Option::<Pubkey>::from(metadata.update_authority),
+            Some(config)
+        );
+        assert_eq!(metadata.name, "wALIEN position #42");
+        assert_eq!(
+            metadata.additional_metadata,
+            vec![
+                ("walien_allocation".to_string(), "7000000000".to_string()),
+                ("usdc_spent".to_string(), "1500000".to_string()),
+            ]
+        );
+        assert!(metadata.tlv_size_of().unwrap() > 0);
+    }
+
+    fn check_holder(
+        mut data: Vec<u8>,
+        program: Pubkey,
+        mint: &Pubkey,
+        holder: &Pubkey,
+    ) -> Result<()> {
+        let key = Pubkey::new_unique();
+        let mut lamports = 0;
+        let info = AccountInfo::new(
+            &key,
+            false,
+            false,
+            &mut lamports,
+            &mut data,
+            &program,
+            false,
+            0,
+        );
+        require_receipt_holder(&info, mint, holder)
+    }
+
+    #[test]
+    fn test_only_the_receipt_holder_passes() {
+        let mint = Pubkey::new_unique();
+        let holder = Pubkey::new_unique();
+        assert!(check_holder(
+            token_account(mint, holder, 1),
+            spl_token_2022::ID,
+            &mint,
+            &holder
+        )
+        .is_ok());
+
+        for data in [
+            token_account(mint, holder, 0),
+            token_account(mint, Pubkey::new_unique(), 1),
+            token_account(Pubkey::new_unique(), holder, 1),
+            vec![0u8; 10],
+        ] {
+            assert_eq!(
+                check_holder(data, spl_token_2022::ID, &mint, &holder).unwrap_err(),
+                ErrorCode::InvalidReceipt.into()
+            );
+        }
+    }
+
+    #[test]
+    fn test_receipt_account_not_owned_by_token_2022_is_rejected() {
+        let mint = Pubkey::new_unique();
+        let holder = Pubkey::new_unique();
+        let spoofed = token_account(mint, holder, 1);
+        assert_eq!(
+            check_holder(spoofed, Pubkey::new_unique(), &mint, &holder).unwrap_err(),
+            ErrorCode::InvalidReceipt.into()
+        );
+    }
+}
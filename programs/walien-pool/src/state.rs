@@ -1,29 +1,323 @@
 use anchor_lang::prelude::*;
 
+use crate::{
+    constants::{
+        BPS_DENOMINATOR, FIRST_POSITION_INDEX, MAX_LIQUIDITY_RANGES, MAX_POOLS,
+        PAUSE_ADMIN_WITHDRAW, PAUSE_BUY, PAUSE_CLAIM, PAUSE_REFUND, PAUSE_TRANSFER,
+    },
+    errors::ErrorCode,
+    merkle::{leaf_hash, verify_proof, MerkleHash},
+};
+
+#[account]
+#[derive(Debug)]
+pub struct PoolRegistry {
+    pub admin: Pubkey,
+    // Set by `propose_registry_admin`, becomes admin once it signs `accept_registry_admin`
+    pub pending_admin: Option<Pubkey>,
+    pub pool_ids: Vec<u64>,
+    pub bump: u8,
+}
+
+impl PoolRegistry {
+    pub const SIZE: usize = 8 + 32 + 33 + 4 + 8 * MAX_POOLS + 1;
+}
+
 #[account]
 #[derive(Debug)]
 pub struct GlobalConfig {
     pub admin: Pubkey,
+    // Set by `propose_admin`, becomes admin once it signs `accept_admin`
+    pub pending_admin: Option<Pubkey>,
+    pub pool_id: u64,
     pub usdc_mint: Pubkey,
     pub walien_mint: Option<Pubkey>,
-    // Flags
+    // Flags, emergency override on top of the schedule below
     pub is_sale_active: bool,
     pub is_claim_active: bool,
+    pub is_transfer_active: bool,
+    // Incident switches, one `PAUSE_*` bit per path
+    pub pause_flags: u8,
+    // Schedule (unix timestamps), sale runs in [sale_start_ts, sale_end_ts)
+    pub sale_start_ts: i64,
+    pub sale_end_ts: i64,
+    pub claim_start_ts: i64,
+    // Vesting from claim_start_ts, no vesting when duration is 0
+    pub vesting_cliff_secs: i64,
+    pub vesting_duration_secs: i64,
+    // Per-transaction USDC bounds, 0 = unbounded
+    pub min_usdc_per_buy: u64,
+    pub max_usdc_per_buy: u64,
+    // Per-wallet USDC contribution bounds across all positions, 0 = unbounded
+    pub min_usdc_per_wallet: u64,
+    pub max_usdc_per_wallet: u64,
+    // Allowlist phase, buyers must prove membership in the merkle tree (see `merkle`)
+    pub is_allowlist_phase: bool,
+    pub merkle_root: Option<[u8; 32]>,
 
     pub available_for_swap_in_usdc: u64,
+    // Minimum raise, the sale fails if total_usdc_raised is below it at sale_end_ts (0 = no soft cap)
+    pub soft_cap_usdc: u64,
+    pub total_usdc_raised: u64,
 
     pub possition_index: u64,
     pub bump: u8,
     ///cheeper then recalculate
     // pool config
     pub tick_upper: i32,
+    /// Swaps use the ranges of the pool's `LiquidityCurve` instead of `[price, tick_upper]` with `liqudity`.
+    pub has_liquidity_curve: bool,
+    /// Swap fee in hundredths of a basis point (1_000_000 = 100%), capped by `orca_math::MAX_FEE_RATE`.
     pub fee_bps: u16,
+    /// Swap fees held in the USDC vault, not yet sent to `fee_recipient`.
+    pub accrued_fees: u64,
+    pub fee_recipient: Pubkey,
+    // Anti-snipe schedule: the fee starts at launch_fee_rate when the sale opens and decays to
+    // fee_bps over fee_decay_secs, linearly or in fee_decay_steps equal steps (0 = linear)
+    pub launch_fee_rate: u16,
+    pub fee_decay_secs: i64,
+    pub fee_decay_steps: u16,
     pub liqudity: u128,
     pub initial_sqrt_price_x64: u128,
+    /// Price the curve started from; refunds never reprice below it.
+    pub floor_sqrt_price_x64: u128,
+    // Paid to `crank_claims` callers per processed position: lamports from this account's
+    // balance above rent exemption, wALIEN from the vault out of crank_walien_budget
+    pub crank_reward_lamports: u64,
+    pub crank_reward_walien: u64,
+    pub crank_walien_budget: u64,
+    /// wALIEN deposited by `fund_referral_budget` and not yet promised to a referrer. Walien
+    /// referral rewards are reserved out of it, so they never come out of the allocations.
+    pub referral_walien_budget: u64,
+    /// Walien referral rewards booked on `Referrer.accrued_walien` and not paid out yet.
+    /// `withdraw_walien` leaves them in the vault.
+    pub referral_walien_owed: u64,
+    /// `buy` mints a receipt NFT per position, see `receipt`.
+    pub mint_position_receipts: bool,
 }
 
 impl GlobalConfig {
     pub const SIZE: usize = 8 + std::mem::size_of::<Self>();
+
+    pub fn is_sale_open(&self, now_ts: i64) -> bool {
+        self.is_sale_active && now_ts >= self.sale_start_ts && now_ts < self.sale_end_ts
+    }
+
+    /// Fails with the path's `*Paused` error while the `PAUSE_*` bit `flag` is set.
+    pub fn require_not_paused(&self, flag: u8) -> Result<()> {
+        if self.pause_flags & flag == 0 {
+            return Ok(());
+        }
+        let error = match flag {
+            PAUSE_BUY => ErrorCode::BuyPaused,
+            PAUSE_CLAIM => ErrorCode::ClaimPaused,
+            PAUSE_REFUND => ErrorCode::RefundPaused,
+            PAUSE_ADMIN_WITHDRAW => ErrorCode::AdminWithdrawPaused,
+            PAUSE_TRANSFER => ErrorCode::TransferPaused,
+            _ => ErrorCode::InvalidPauseFlags,
+        };
+        Err(error.into())
+    }
+
+    pub fn require_sale_open(&self, now_ts: i64) -> Result<()> {
+        self.require_not_paused(PAUSE_BUY)?;
+        require!(self.is_sale_active, ErrorCode::SaleNotActive);
+        require!(now_ts >= self.sale_start_ts, ErrorCode::SaleNotStarted);
+        require!(now_ts < self.sale_end_ts, ErrorCode::SaleEnded);
+        Ok(())
+    }
+
+    /// Takes up to `amount` out of `referral_walien_budget` and books it as owed, returns what
+    /// was reserved.
+    pub fn reserve_referral_walien(&mut self, amount: u64) -> u64 {
+        let reserved = amount.min(self.referral_walien_budget);
+        self.referral_walien_budget -= reserved;
+        self.referral_walien_owed += reserved;
+        reserved
+    }
+
+    /// Books `amount` of owed Walien referral rewards as paid out.
+    pub fn pay_referral_walien(&mut self, amount: u64) -> Result<()> {
+        self.referral_walien_owed = self
+            .referral_walien_owed
+            .checked_sub(amount)
+            .ok_or(ErrorCode::AmountCalcOverflow)?;
+        Ok(())
+    }
+
+    /// Part of the Walien `vault_balance` the admin may withdraw: everything but the owed
+    /// referral rewards and the `owed_fee` the vault pays on top to transfer them.
+    pub fn withdrawable_walien(&self, vault_balance: u64, owed_fee: u64) -> u64 {
+        vault_balance.saturating_sub(self.referral_walien_owed.saturating_add(owed_fee))
+    }
+
+    /// A buy ever went through; positions and refund repricing depend on the curve it used.
+    pub fn has_positions(&self) -> bool {
+        self.possition_index != FIRST_POSITION_INDEX
+    }
+
+    /// Non-empty sale window `[sale_start_ts, sale_end_ts)`, with claims opening only once it's
+    /// over, when the soft cap outcome is known.
+    pub fn validate_schedule(
+        sale_start_ts: i64,
+        sale_end_ts: i64,
+        claim_start_ts: i64,
+    ) -> Result<()> {
+        require!(sale_start_ts < sale_end_ts, ErrorCode::InvalidSchedule);
+        require!(claim_start_ts >= sale_end_ts, ErrorCode::InvalidSchedule);
+        Ok(())
+    }
+
+    /// `validate_schedule` for a new schedule; windows that started by `now_ts` must be passed
+    /// unchanged, the others must still start in the future.
+    pub fn validate_reschedule(
+        &self,
+        sale_start_ts: i64,
+        sale_end_ts: i64,
+        claim_start_ts: i64,
+        now_ts: i64,
+    ) -> Result<()> {
+        Self::validate_schedule(sale_start_ts, sale_end_ts, claim_start_ts)?;
+
+        if self.sale_start_ts <= now_ts {
+            require!(
+                sale_start_ts == self.sale_start_ts && sale_end_ts == self.sale_end_ts,
+                ErrorCode::WindowAlreadyStarted
+            );
+        } else {
+            require!(sale_start_ts > now_ts, ErrorCode::InvalidSchedule);
+        }
+
+        if self.claim_start_ts <= now_ts {
+            require!(
+                claim_start_ts == self.claim_start_ts,
+                ErrorCode::WindowAlreadyStarted
+            );
+        } else {
+            require!(claim_start_ts > now_ts, ErrorCode::InvalidSchedule);
+        }
+        Ok(())
+    }
+
+    /// Sale window is over and the soft cap was not reached, positions are refundable.
+    pub fn is_sale_failed(&self, now_ts: i64) -> bool {
+        now_ts >= self.sale_end_ts && self.total_usdc_raised < self.soft_cap_usdc
+    }
+
+    /// Extra seed of a pay mint's vault, `["vault_usdc", config, mint]`. Empty for `usdc_mint`,
+    /// whose vault keeps its `["vault_usdc", config]` address.
+    pub fn pay_vault_seed<'a>(&self, mint: &'a Pubkey) -> &'a [u8] {
+        if *mint == self.usdc_mint {
+            &[]
+        } else {
+            mint.as_ref()
+        }
+    }
+
+    pub fn require_claim_open(&self, now_ts: i64) -> Result<()> {
+        self.require_not_paused(PAUSE_CLAIM)?;
+        // nothing to pay out before `set_walien`, whatever the schedule says
+        require!(self.walien_mint.is_some(), ErrorCode::WalienIsNotSet);
+        require!(!self.is_sale_failed(now_ts), ErrorCode::SaleFailed);
+        require!(self.is_claim_active, ErrorCode::ClaimIsNotActive);
+        require!(now_ts >= self.claim_start_ts, ErrorCode::ClaimNotStarted);
+        Ok(())
+    }
+
+    pub fn exceeds_max_purchase(&self, usdc_amount: u64) -> bool {
+        self.max_usdc_per_buy != 0 && usdc_amount > self.max_usdc_per_buy
+    }
+
+    pub fn below_min_purchase(&self, usdc_amount: u64) -> bool {
+        usdc_amount < self.min_usdc_per_buy
+    }
+
+    pub fn has_fee_schedule(&self) -> bool {
+        self.fee_decay_secs > 0 && self.launch_fee_rate > self.fee_bps
+    }
+
+    /// Fee rate in effect at `now_ts`, see `launch_fee_rate`.
+    pub fn fee_rate_at(&self, now_ts: i64) -> u16 {
+        if !self.has_fee_schedule() {
+            return self.fee_bps;
+        }
+        let elapsed = now_ts.saturating_sub(self.sale_start_ts).max(0);
+        if elapsed >= self.fee_decay_secs {
+            return self.fee_bps;
+        }
+        let spread = (self.launch_fee_rate - self.fee_bps) as i128;
+        let decayed = if self.fee_decay_steps == 0 {
+            spread * elapsed as i128 / self.fee_decay_secs as i128
+        } else {
+            let steps = self.fee_decay_steps as i128;
+            let step = elapsed as i128 * steps / self.fee_decay_secs as i128;
+            spread * step / steps
+        };
+        self.launch_fee_rate - decayed as u16
+    }
+
+    /// Checks a wallet's total contribution after a buy against the per-wallet bounds.
+    pub fn require_wallet_contribution(&self, wallet_total_usdc: u64) -> Result<()> {
+        require!(
+            self.max_usdc_per_wallet == 0 || wallet_total_usdc <= self.max_usdc_per_wallet,
+            ErrorCode::WalletCapExceeded
+        );
+        require!(
+            wallet_total_usdc >= self.min_usdc_per_wallet,
+            ErrorCode::BelowMinContribution
+        );
+        Ok(())
+    }
+
+    /// No-op outside the allowlist phase.
+    pub fn require_allowlisted(
+        &self,
+        wallet: &Pubkey,
+        allowlist_proof: Option<&AllowlistProof>,
+        wallet_total_usdc: u64,
+    ) -> Result<()> {
+        if !self.is_allowlist_phase {
+            return Ok(());
+        }
+        let root = self.merkle_root.ok_or(ErrorCode::AllowlistRootNotSet)?;
+        let allowlist_proof = allowlist_proof.ok_or(ErrorCode::InvalidMerkleProof)?;
+        require!(
+            verify_proof(
+                &allowlist_proof.proof,
+                &root,
+                &leaf_hash(wallet, allowlist_proof.cap)
+            ),
+            ErrorCode::InvalidMerkleProof
+        );
+        require!(
+            allowlist_proof.cap == 0 || wallet_total_usdc <= allowlist_proof.cap,
+            ErrorCode::AllowlistCapExceeded
+        );
+        Ok(())
+    }
+
+    /// Part of `allocation` released by `now_ts`: nothing before the cliff, then linear.
+    pub fn vested_amount(&self, allocation: u64, now_ts: i64) -> Result<u64> {
+        let elapsed = now_ts.saturating_sub(self.claim_start_ts);
+        if elapsed < 0 || elapsed < self.vesting_cliff_secs {
+            return Ok(0);
+        }
+        if elapsed >= self.vesting_duration_secs {
+            return Ok(allocation);
+        }
+        let vested = (allocation as u128)
+            .checked_mul(elapsed as u128)
+            .ok_or(ErrorCode::MultiplicationOverflow)?
+            / self.vesting_duration_secs as u128;
+        Ok(u64::try_from(vested).map_err(ErrorCode::from)?)
+    }
+}
+
+/// Buyer's allowlist leaf: its USDC cap (0 = none) and the merkle proof for it.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct AllowlistProof {
+    pub cap: u64,
+    pub proof: Vec<MerkleHash>,
 }
 
 #[account]
@@ -33,10 +327,51 @@ pub struct UserPosition {
     pub index: u64,
     pub usdc_spent: u64,
     pub walien_allocation: u64,
+    pub claimed_amount: u64,
     pub last_buy_timestamp: i64,
+    /// Token the position was paid in, refunds and proceeds are paid in it too.
+    pub pay_mint: Pubkey,
+    /// Receipt NFT of the position; whoever holds it may claim or refund the position.
+    pub receipt_mint: Option<Pubkey>,
 }
 impl UserPosition {
     pub const SIZE: usize = 8 + std::mem::size_of::<Self>();
+
+    /// USDC backing the first `claimed` tokens of the allocation.
+    pub fn usdc_released(&self, claimed: u64) -> Result<u64> {
+        if self.walien_allocation == 0 {
+            return Ok(0);
+        }
+        let released = (self.usdc_spent as u128)
+            .checked_mul(claimed as u128)
+            .ok_or(ErrorCode::MultiplicationOverflow)?
+            / self.walien_allocation as u128;
+        Ok(u64::try_from(released).map_err(ErrorCode::from)?)
+    }
+
+    /// Folds `other` into this position. Both must be unclaimed, since `usdc_released`
+    /// only stays proportional while nothing has been claimed, and paid in the same mint.
+    pub fn absorb(&mut self, other: &UserPosition) -> Result<()> {
+        require!(
+            self.claimed_amount == 0 && other.claimed_amount == 0,
+            ErrorCode::PositionPartiallyClaimed
+        );
+        require_keys_eq!(self.pay_mint, other.pay_mint, ErrorCode::InvalidPayMint);
+        require!(
+            self.receipt_mint.is_none() && other.receipt_mint.is_none(),
+            ErrorCode::PositionHasReceipt
+        );
+        self.usdc_spent = self
+            .usdc_spent
+            .checked_add(other.usdc_spent)
+            .ok_or(ErrorCode::AmountCalcOverflow)?;
+        self.walien_allocation = self
+            .walien_allocation
+            .checked_add(other.walien_allocation)
+            .ok_or(ErrorCode::AmountCalcOverflow)?;
+        self.last_buy_timestamp = self.last_buy_timestamp.max(other.last_buy_timestamp);
+        Ok(())
+    }
 }
 
 #[account]
@@ -51,4 +386,499 @@ pub struct UserSummary {
 }
 impl UserSummary {
     pub const SIZE: usize = 8 + std::mem::size_of::<Self>();
+
+    /// Nothing left to claim or refund, the summary can be closed.
+    pub fn is_empty(&self) -> bool {
+        self.total_walien_alloc == 0 && self.total_usdc_locked == 0
+    }
+
+    /// Moves the unclaimed allocation and locked USDC of `position` from this summary to
+    /// `new_owner`'s summary `to`, which may be freshly created. Returns the moved
+    /// `(walien_amount, usdc_amount)`.
+    pub fn move_position(
+        &mut self,
+        to: &mut UserSummary,
+        new_owner: Pubkey,
+        position: &UserPosition,
+    ) -> Result<(u64, u64)> {
+        let walien_amount = position.walien_allocation - position.claimed_amount;
+        let usdc_amount = position.usdc_spent - position.usdc_released(position.claimed_amount)?;
+
+        self.total_walien_alloc = self
+            .total_walien_alloc
+            .checked_sub(walien_amount)
+            .ok_or(ErrorCode::UserSummaryUnderflow)?;
+        self.total_usdc_locked = self
+            .total_usdc_locked
+            .checked_sub(usdc_amount)
+            .ok_or(ErrorCode::UserSummaryUnderflow)?;
+
+        to.authority = new_owner;
+        to.total_walien_alloc = to
+            .total_walien_alloc
+            .checked_add(walien_amount)
+            .ok_or(ErrorCode::AmountCalcOverflow)?;
+        to.total_usdc_locked = to
+            .total_usdc_locked
+            .checked_add(usdc_amount)
+            .ok_or(ErrorCode::AmountCalcOverflow)?;
+        to.last_buy_timestamp = to.last_buy_timestamp.max(position.last_buy_timestamp);
+        Ok((walien_amount, usdc_amount))
+    }
+}
+
+/// Stablecoin accepted on top of `usdc_mint`, 1:1 with the quote unit (USDC).
+#[account]
+#[derive(Debug)]
+pub struct PayMint {
+    pub global_config: Pubkey,
+    pub mint: Pubkey,
+    pub decimals: u8,
+    /// Swap fees paid in this mint, in its own units.
+    pub accrued_fees: u64,
+    pub bump: u8,
+}
+impl PayMint {
+    pub const SIZE: usize = 8 + std::mem::size_of::<Self>();
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LiquidityRange {
+    /// The range starts at the previous range's tick_upper (the floor price for the first one).
+    pub tick_upper: i32,
+    pub liquidity: u128,
+}
+
+#[account]
+#[derive(Debug)]
+pub struct LiquidityCurve {
+    pub global_config: Pubkey,
+    /// Ordered by strictly increasing tick_upper.
+    pub ranges: Vec<LiquidityRange>,
+    pub bump: u8,
+}
+impl LiquidityCurve {
+    pub const SIZE: usize = 8 + 32 + 4 + (4 + 16) * MAX_LIQUIDITY_RANGES + 1;
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReferralRewardKind {
+    /// Share of the swap fee, paid from the USDC vault.
+    Usdc,
+    /// Bonus on top of the buyer's allocation, paid from the Walien vault.
+    Walien,
+}
+
+#[account]
+#[derive(Debug)]
+pub struct Referrer {
+    pub authority: Pubkey,
+    pub global_config: Pubkey,
+    pub reward_kind: ReferralRewardKind,
+    pub reward_rate_bps: u16,
+    pub accrued_usdc: u64,
+    pub accrued_walien: u64,
+    pub total_referred_usdc: u64,
+    pub bump: u8,
+}
+impl Referrer {
+    pub const SIZE: usize = 8 + std::mem::size_of::<Self>();
+
+    /// (usdc, walien) reward for a referred buy.
+    pub fn reward_for(&self, fee_amount: u64, walien_amount: u64) -> Result<(u64, u64)> {
+        let base = match self.reward_kind {
+            ReferralRewardKind::Usdc => fee_amount,
+            ReferralRewardKind::Walien => walien_amount,
+        };
+        let reward = (base as u128)
+            .checked_mul(self.reward_rate_bps as u128)
+            .ok_or(ErrorCode::MultiplicationOverflow)?
+            / BPS_DENOMINATOR as u128;
+        let reward = u64::try_from(reward).map_err(ErrorCode::from)?;
+        Ok(match self.reward_kind {
+            ReferralRewardKind::Usdc => (reward, 0),
+            ReferralRewardKind::Walien => (0, reward),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vesting_config(cliff: i64, duration: i64) -> GlobalConfig {
+        GlobalConfig {
+            admin: Pubkey::default(),
+            pending_admin: None,
+            pool_id: 0,
+            usdc_mint: Pubkey::default(),
+            walien_mint: Some(Pubkey::new_unique()),
+            is_sale_active: true,
+            is_claim_active: true,
+            is_transfer_active: true,
+            pause_flags: 0,
+            sale_start_ts: 0,
+            sale_end_ts: 100,
+            claim_start_ts: 1_000,
+            vesting_cliff_secs: cliff,
+            vesting_duration_secs: duration,
+            min_usdc_per_wallet: 0,
+            max_usdc_per_wallet: 0,
+            is_allowlist_phase: false,
+            merkle_root: None,
+            min_usdc_per_buy: 0,
+            max_usdc_per_buy: 0,
+            available_for_swap_in_usdc: 0,
+            soft_cap_usdc: 0,
+            total_usdc_raised: 0,
+            possition_index: 1,
+            bump: 0,
+            tick_upper: 0,
+            has_liquidity_curve: false,
+            fee_bps: 0,
+            accrued_fees: 0,
+            fee_recipient: Pubkey::default(),
+            launch_fee_rate: 0,
+            fee_decay_secs: 0,
+            fee_decay_steps: 0,
+            liqudity: 0,
+            initial_sqrt_price_x64: 0,
+            floor_sqrt_price_x64: 0,
+            crank_reward_lamports: 0,
+            crank_reward_walien: 0,
+            crank_walien_budget: 0,
+            referral_walien_budget: 0,
+            referral_walien_owed: 0,
+            mint_position_receipts: false,
+        }
+    }
+
+    #[test]
+    fn test_no_vesting_releases_everything_at_claim_start() {
+        let cfg = vesting_config(0, 0);
+        assert_eq!(cfg.vested_amount(1_000, 999).unwrap(), 0);
+        assert_eq!(cfg.vested_amount(1_000, 1_000).unwrap(), 1_000);
+    }
+
+    #[test]
+    fn test_linear_vesting_with_cliff() {
+        let cfg = vesting_config(100, 400);
+        assert_eq!(cfg.vested_amount(1_000, 1_099).unwrap(), 0);
+        assert_eq!(cfg.vested_amount(1_000, 1_100).unwrap(), 250);
+        assert_eq!(cfg.vested_amount(1_000, 1_200).unwrap(), 500);
+        assert_eq!(cfg.vested_amount(1_000, 1_400).unwrap(), 1_000);
+        assert_eq!(cfg.vested_amount(1_000, i64::MAX).unwrap(), 1_000);
+    }
+
+    #[test]
+    fn test_fee_decays_linearly_to_base() {
+        let mut cfg = vesting_config(0, 0);
+        cfg.fee_bps = 3_000;
+        cfg.launch_fee_rate = 50_000;
+        cfg.fee_decay_secs = 100;
+        assert_eq!(cfg.fee_rate_at(-10), 50_000);
+        assert_eq!(cfg.fee_rate_at(0), 50_000);
+        assert_eq!(cfg.fee_rate_at(50), 26_500);
+        assert_eq!(cfg.fee_rate_at(99), 3_470);
+        assert_eq!(cfg.fee_rate_at(100), 3_000);
+    }
+
+    #[test]
+    fn test_fee_decays_in_steps() {
+        let mut cfg = vesting_config(0, 0);
+        cfg.fee_bps = 0;
+        cfg.launch_fee_rate = 40_000;
+        cfg.fee_decay_secs = 100;
+        cfg.fee_decay_steps = 4;
+        assert_eq!(cfg.fee_rate_at(24), 40_000);
+        assert_eq!(cfg.fee_rate_at(25), 30_000);
+        assert_eq!(cfg.fee_rate_at(74), 20_000);
+        assert_eq!(cfg.fee_rate_at(75), 10_000);
+        assert_eq!(cfg.fee_rate_at(100), 0);
+    }
+
+    #[test]
+    fn test_usdc_released_sums_to_usdc_spent() {
+        let position = UserPosition {
+            authority: Pubkey::default(),
+            index: 1,
+            usdc_spent: 1_000_001,
+            walien_allocation: 3_000,
+            claimed_amount: 0,
+            last_buy_timestamp: 0,
+            pay_mint: Pubkey::default(),
+            receipt_mint: None,
+        };
+        let steps = [0u64, 1, 999, 1_500, 2_999, 3_000];
+        let forwarded: u64 = steps
+            .windows(2)
+            .map(|w| position.usdc_released(w[1]).unwrap() - position.usdc_released(w[0]).unwrap())
+            .sum();
+        assert_eq!(forwarded, position.usdc_spent);
+    }
+
+    #[test]
+    fn test_absorb_sums_positions_and_keeps_latest_buy() {
+        let position = |usdc_spent, walien_allocation, last_buy_timestamp| UserPosition {
+            authority: Pubkey::default(),
+            index: 1,
+            usdc_spent,
+            walien_allocation,
+            claimed_amount: 0,
+            last_buy_timestamp,
+            pay_mint: Pubkey::default(),
+            receipt_mint: None,
+        };
+        let mut target = position(1_000, 3_000, 20);
+        target.absorb(&position(500, 1_000, 50)).unwrap();
+        target.absorb(&position(250, 400, 10)).unwrap();
+        assert_eq!(target.usdc_spent, 1_750);
+        assert_eq!(target.walien_allocation, 4_400);
+        assert_eq!(target.last_buy_timestamp, 50);
+
+        let mut claimed = position(100, 100, 0);
+        claimed.claimed_amount = 1;
+        assert!(target.absorb(&claimed).is_err());
+
+        let mut other_mint = position(100, 100, 0);
+        other_mint.pay_mint = Pubkey::new_unique();
+        assert!(target.absorb(&other_mint).is_err());
+
+        let mut with_receipt = position(100, 100, 0);
+        with_receipt.receipt_mint = Some(Pubkey::new_unique());
+        assert!(target.absorb(&with_receipt).is_err());
+        assert_eq!(target.usdc_spent, 1_750);
+    }
+
+    #[test]
+    fn test_sale_fails_below_soft_cap_after_end() {
+        let mut cfg = vesting_config(0, 0);
+        cfg.soft_cap_usdc = 1_000;
+        cfg.total_usdc_raised = 999;
+        assert!(!cfg.is_sale_failed(99));
+        assert!(cfg.is_sale_failed(100));
+        assert!(cfg.require_claim_open(1_000).is_err());
+
+        cfg.total_usdc_raised = 1_000;
+        assert!(!cfg.is_sale_failed(100));
+        assert!(cfg.require_claim_open(1_000).is_ok());
+    }
+
+    #[test]
+    fn test_claims_wait_for_the_walien_mint() {
+        let mut cfg = vesting_config(0, 0);
+        assert!(cfg.require_claim_open(1_000).is_ok());
+        cfg.walien_mint = None;
+        assert_eq!(
+            cfg.require_claim_open(1_000).unwrap_err(),
+            ErrorCode::WalienIsNotSet.into()
+        );
+    }
+
+    #[test]
+    fn test_pause_flags_block_only_their_path() {
+        let mut cfg = vesting_config(0, 0);
+        cfg.pause_flags = PAUSE_CLAIM | PAUSE_TRANSFER;
+        assert!(cfg.require_sale_open(50).is_ok());
+        assert_eq!(
+            cfg.require_claim_open(1_000).unwrap_err(),
+            ErrorCode::ClaimPaused.into()
+        );
+        assert_eq!(
+            cfg.require_not_paused(PAUSE_TRANSFER).unwrap_err(),
+            ErrorCode::TransferPaused.into()
+        );
+        assert!(cfg.require_not_paused(PAUSE_REFUND).is_ok());
+        assert!(cfg.require_not_paused(PAUSE_ADMIN_WITHDRAW).is_ok());
+
+        cfg.pause_flags = PAUSE_BUY;
+        assert_eq!(
+            cfg.require_sale_open(50).unwrap_err(),
+            ErrorCode::BuyPaused.into()
+        );
+        assert!(cfg.require_claim_open(1_000).is_ok());
+    }
+
+    #[test]
+    fn test_schedule_needs_a_sale_window_before_claims() {
+        assert!(GlobalConfig::validate_schedule(0, 100, 100).is_ok());
+        assert!(GlobalConfig::validate_schedule(0, 100, 1_000).is_ok());
+        // empty or inverted sale window
+        assert!(GlobalConfig::validate_schedule(100, 100, 1_000).is_err());
+        assert!(GlobalConfig::validate_schedule(101, 100, 1_000).is_err());
+        // claims opening mid-sale
+        assert_eq!(
+            GlobalConfig::validate_schedule(0, 100, 99).unwrap_err(),
+            ErrorCode::InvalidSchedule.into()
+        );
+    }
+
+    #[test]
+    fn test_started_windows_cannot_be_rescheduled() {
+        // sale [0, 100), claims at 1_000
+        let cfg = vesting_config(0, 0);
+
+        // before anything started every window can move, but not into the past
+        let mut not_started = vesting_config(0, 0);
+        not_started.sale_start_ts = 10;
+        assert!(not_started.validate_reschedule(20, 200, 2_000, 5).is_ok());
+        assert!(not_started.validate_reschedule(4, 200, 2_000, 5).is_err());
+
+        // sale started: its window is fixed, claims can still move
+        assert!(cfg.validate_reschedule(0, 100, 2_000, 50).is_ok());
+        assert_eq!(
+            cfg.validate_reschedule(0, 150, 2_000, 50).unwrap_err(),
+            ErrorCode::WindowAlreadyStarted.into()
+        );
+        assert_eq!(
+            cfg.validate_reschedule(1, 100, 2_000, 50).unwrap_err(),
+            ErrorCode::WindowAlreadyStarted.into()
+        );
+        assert!(cfg.validate_reschedule(0, 100, 40, 50).is_err());
+
+        // claims started: nothing moves
+        assert!(cfg.validate_reschedule(0, 100, 1_000, 1_500).is_ok());
+        assert_eq!(
+            cfg.validate_reschedule(0, 100, 2_000, 1_500).unwrap_err(),
+            ErrorCode::WindowAlreadyStarted.into()
+        );
+    }
+
+    #[test]
+    fn test_has_positions_after_the_first_buy() {
+        let mut cfg = vesting_config(0, 0);
+        cfg.possition_index = FIRST_POSITION_INDEX;
+        assert!(!cfg.has_positions());
+        cfg.possition_index += 1;
+        assert!(cfg.has_positions());
+    }
+
+    #[test]
+    fn test_referral_walien_is_capped_by_the_budget() {
+        let mut cfg = vesting_config(0, 0);
+        cfg.referral_walien_budget = 150;
+        assert_eq!(cfg.reserve_referral_walien(100), 100);
+        assert_eq!(cfg.referral_walien_budget, 50);
+        assert_eq!(cfg.reserve_referral_walien(100), 50);
+        assert_eq!(cfg.referral_walien_budget, 0);
+        assert_eq!(cfg.reserve_referral_walien(100), 0);
+        assert_eq!(cfg.referral_walien_owed, 150);
+    }
+
+    #[test]
+    fn test_owed_referral_walien_stays_in_the_vault() {
+        let mut cfg = vesting_config(0, 0);
+        cfg.referral_walien_budget = 150;
+        cfg.reserve_referral_walien(100);
+        assert_eq!(cfg.referral_walien_owed, 100);
+        assert_eq!(cfg.withdrawable_walien(1_000, 0), 900);
+        assert_eq!(cfg.withdrawable_walien(1_000, 5), 895);
+        assert_eq!(cfg.withdrawable_walien(60, 0), 0);
+
+        cfg.pay_referral_walien(40).unwrap();
+        assert_eq!(cfg.referral_walien_owed, 60);
+        assert_eq!(cfg.withdrawable_walien(1_000, 0), 940);
+        assert_eq!(
+            cfg.pay_referral_walien(61).unwrap_err(),
+            ErrorCode::AmountCalcOverflow.into()
+        );
+        cfg.pay_referral_walien(60).unwrap();
+        assert_eq!(cfg.withdrawable_walien(1_000, 0), 1_000);
+    }
+
+    #[test]
+    fn test_wallet_contribution_bounds() {
+        let mut cfg = vesting_config(0, 0);
+        cfg.min_usdc_per_wallet = 100;
+        cfg.max_usdc_per_wallet = 1_000;
+
+        // exactly at the cap passes, one over fails
+        assert!(cfg.require_wallet_contribution(1_000).is_ok());
+        assert_eq!(
+            cfg.require_wallet_contribution(1_001).unwrap_err(),
+            ErrorCode::WalletCapExceeded.into()
+        );
+
+        // a first buy has to reach the minimum on its own
+        assert!(cfg.require_wallet_contribution(100).is_ok());
+        assert_eq!(
+            cfg.require_wallet_contribution(99).unwrap_err(),
+            ErrorCode::BelowMinContribution.into()
+        );
+
+        // 0 disables the cap
+        cfg.max_usdc_per_wallet = 0;
+        assert!(cfg.require_wallet_contribution(u64::MAX).is_ok());
+    }
+
+    fn summary(authority: Pubkey, total_usdc_locked: u64, total_walien_alloc: u64) -> UserSummary {
+        UserSummary {
+            authority,
+            total_usdc_locked,
+            total_walien_alloc,
+            last_buy_timestamp: 10,
+            global_index_position: 0,
+            index_position: 0,
+        }
+    }
+
+    fn transferred_position(claimed_amount: u64) -> UserPosition {
+        UserPosition {
+            authority: Pubkey::new_unique(),
+            index: 0,
+            usdc_spent: 300,
+            walien_allocation: 1_000,
+            claimed_amount,
+            last_buy_timestamp: 20,
+            pay_mint: Pubkey::default(),
+            receipt_mint: None,
+        }
+    }
+
+    #[test]
+    fn test_move_position_fills_a_new_summary_and_empties_the_old_one() {
+        let new_owner = Pubkey::new_unique();
+        let mut from = summary(Pubkey::new_unique(), 225, 750);
+        // freshly created by `init_if_needed`
+        let mut to = summary(Pubkey::default(), 0, 0);
+        to.last_buy_timestamp = 0;
+
+        let moved = from
+            .move_position(&mut to, new_owner, &transferred_position(250))
+            .unwrap();
+        assert_eq!(moved, (750, 225));
+        assert!(from.is_empty());
+        assert_eq!(to.authority, new_owner);
+        assert_eq!((to.total_walien_alloc, to.total_usdc_locked), (750, 225));
+        assert_eq!(to.last_buy_timestamp, 20);
+    }
+
+    #[test]
+    fn test_move_position_adds_to_an_existing_summary() {
+        let new_owner = Pubkey::new_unique();
+        let mut from = summary(Pubkey::new_unique(), 400, 1_500);
+        let mut to = summary(new_owner, 100, 200);
+        to.last_buy_timestamp = 30;
+
+        from.move_position(&mut to, new_owner, &transferred_position(0))
+            .unwrap();
+        assert_eq!(
+            (from.total_walien_alloc, from.total_usdc_locked),
+            (500, 100)
+        );
+        assert!(!from.is_empty());
+        assert_eq!((to.total_walien_alloc, to.total_usdc_locked), (1_200, 400));
+        assert_eq!(to.last_buy_timestamp, 30);
+    }
+
+    #[test]
+    fn test_move_position_underflow_fails() {
+        let mut from = summary(Pubkey::new_unique(), 300, 999);
+        let mut to = summary(Pubkey::default(), 0, 0);
+        assert_eq!(
+            from.move_position(&mut to, Pubkey::new_unique(), &transferred_position(0))
+                .unwrap_err(),
+            ErrorCode::UserSummaryUnderflow.into()
+        );
+    }
 }
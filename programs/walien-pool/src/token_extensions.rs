@@ -0,0 +1,237 @@
+//! Token-2022 extension handling for pool token transfers.
+//!
+//! The wALIEN mint is checked once in `set_walien`. Transfer fees are grossed up so that
+//! recipients get the full amount, and transfer hooks get their extra accounts from the
+//! instruction's remaining accounts.
+
+use anchor_lang::prelude::*;
+use anchor_spl::token_2022::{
+    spl_token_2022::{
+        extension::{
+            default_account_state::DefaultAccountState, transfer_fee::TransferFeeConfig,
+            BaseStateWithExtensions, ExtensionType, StateWithExtensions,
+        },
+        onchain::invoke_transfer_checked,
+        state::{AccountState, Mint},
+    },
+    Token2022, TransferChecked,
+};
+
+use crate::errors::ErrorCode;
+
+/// Rejects wALIEN mint extensions the pool cannot honour.
+///
+/// NonTransferable and Pausable block payouts, PermanentDelegate can move vault funds,
+/// a frozen DefaultAccountState freezes the accounts claims pay into, and confidential
+/// extensions hide balances. Anything not listed here is rejected as well.
+pub fn validate_walien_mint(mint_data: &[u8]) -> Result<()> {
+    let mint = StateWithExtensions::<Mint>::unpack(mint_data)?;
+    for extension in mint.get_extension_types()? {
+        match extension {
+            ExtensionType::TransferFeeConfig
+            | ExtensionType::TransferHook
+            | ExtensionType::MintCloseAuthority
+            | ExtensionType::InterestBearingConfig
+            | ExtensionType::MetadataPointer
+            | ExtensionType::TokenMetadata
+            | ExtensionType::GroupPointer
+            | ExtensionType::TokenGroup
+            | ExtensionType::GroupMemberPointer
+            | ExtensionType::TokenGroupMember => {}
+            ExtensionType::DefaultAccountState => {
+                let default_state = mint.get_extension::<DefaultAccountState>()?;
+                require!(
+                    default_state.state == AccountState::Initialized as u8,
+                    ErrorCode::UnsupportedWalienExtension
+                );
+            }
+            _ => return Err(ErrorCode::UnsupportedWalienExtension.into()),
+        }
+    }
+    Ok(())
+}
+
+/// Amount to send so that `amount` arrives after the mint's transfer fee at `epoch`.
+pub fn gross_up_transfer_fee(mint_data: &[u8], epoch: u64, amount: u64) -> Result<u64> {
+    let mint = StateWithExtensions::<Mint>::unpack(mint_data)?;
+    let Ok(transfer_fee_config) = mint.get_extension::<TransferFeeConfig>() else {
+        return Ok(amount);
+    };
+    let fee = transfer_fee_config
+        .calculate_inverse_epoch_fee(epoch, amount)
+        .ok_or(ErrorCode::AmountCalcOverflow)?;
+    Ok(amount
+        .checked_add(fee)
+        .ok_or(ErrorCode::AmountCalcOverflow)?)
+}
+
+/// Amount to send so that `amount` arrives, for Token-2022 mints with a transfer fee.
+pub fn amount_with_transfer_fee(mint: &AccountInfo, amount: u64) -> Result<u64> {
+    if *mint.owner != Token2022::id() {
+        return Ok(amount);
+    }
+    let data = mint.try_borrow_data()?;
+    gross_up_transfer_fee(&data, Clock::get()?.epoch, amount)
+}
+
+/// `transfer_checked` that resolves transfer hook extra accounts from the context's
+/// remaining accounts; a plain `transfer_checked` for mints without a hook.
+pub fn transfer_checked_with_hook<'info>(
+    ctx: CpiContext<'_, '_, '_, 'info, TransferChecked<'info>>,
+    amount: u64,
+    decimals: u8,
+) -> Result<()> {
+    invoke_transfer_checked(
+        ctx.program.key,
+        ctx.accounts.from,
+        ctx.accounts.mint,
+        ctx.accounts.to,
+        ctx.accounts.authority,
+        &ctx.remaining_accounts,
+        amount,
+        decimals,
+        ctx.signer_seeds,
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::solana_program::program_pack::Pack;
+    use anchor_spl::token_2022::spl_token_2022::extension::BaseStateWithExtensionsMut;
+    use anchor_spl::token_2022::spl_token_2022::extension::{
+        non_transferable::NonTransferable, permanent_delegate::PermanentDelegate,
+        transfer_hook::TransferHook, StateWithExtensionsMut,
+    };
+
+    fn mint_data(
+        extensions: &[ExtensionType],
+        init: impl FnOnce(&mut StateWithExtensionsMut<Mint>),
+    ) -> Vec<u8> {
+        let len = ExtensionType::try_calculate_account_len::<Mint>(extensions).unwrap();
+        let mut data = vec![0u8; len];
+        let mut state = StateWithExtensionsMut::<Mint>::unpack_uninitialized(&mut data).unwrap();
+        init(&mut state);
+        state.base = Mint {
+            decimals: 9,
+            is_initialized: true,
+            ..Default::default()
+        };
+        state.pack_base();
+        state.init_account_type().unwrap();
+        data
+    }
+
+    #[test]
+    fn test_plain_mints_are_supported() {
+        let mut classic = vec![0u8; Mint::LEN];
+        Mint {
+            decimals: 9,
+            is_initialized: true,
+            ..Default::default()
+        }
+        .pack_into_slice(&mut classic);
+        assert!(validate_walien_mint(&classic).is_ok());
+        assert!(validate_walien_mint(&mint_data(&[], |_| {})).is_ok());
+        assert_eq!(gross_up_transfer_fee(&classic, 0, 1_000).unwrap(), 1_000);
+    }
+
+    #[test]
+    fn test_transfer_fee_is_grossed_up() {
+        let data = mint_data(&[ExtensionType::TransferFeeConfig], |state| {
+            let config = state.init_extension::<TransferFeeConfig>(true).unwrap();
+            config.newer_transfer_fee.transfer_fee_basis_points = 250.into();
+            config.newer_transfer_fee.maximum_fee = u64::MAX.into();
+            config.older_transfer_fee = config.newer_transfer_fee;
+        });
+        assert!(validate_walien_mint(&data).is_ok());
+
+        let mint = StateWithExtensions::<Mint>::unpack(&data).unwrap();
+        let config = mint.get_extension::<TransferFeeConfig>().unwrap();
+        for amount in [1u64, 999, 1_000_000_000, 123_456_789_012] {
+            let gross = gross_up_transfer_fee(&data, 0, amount).unwrap();
+            assert!(gross > amount);
+            let fee = config.calculate_epoch_fee(0, gross).unwrap();
+            assert_eq!(gross - fee, amount);
+        }
+    }
+
+    #[test]
+    fn test_fee_reserve_covers_split_payouts() {
+        // the README's reserve: ceil(total * bps / (10_000 - bps)) plus one unit per transfer
+        let bps = 250u64;
+        let data = mint_data(&[ExtensionType::TransferFeeConfig], |state| {
+            let config = state.init_extension::<TransferFeeConfig>(true).unwrap();
+            config.newer_transfer_fee.transfer_fee_basis_points = (bps as u16).into();
+            config.newer_transfer_fee.maximum_fee = u64::MAX.into();
+            config.older_transfer_fee = config.newer_transfer_fee;
+        });
+
+        // three allocations, each claimed in uneven vesting tranches
+        let payouts = [
+            [1_000_000_001u64, 333_333_333, 7, 666_666_659],
+            [5u64, 5, 5, 5],
+            [123_456_789u64, 1, 987_654_321, 42],
+        ];
+        let total: u64 = payouts.iter().flatten().sum();
+        let transfers = payouts.iter().flatten().count() as u64;
+        let paid: u64 = payouts
+            .iter()
+            .flatten()
+            .map(|amount| gross_up_transfer_fee(&data, 0, *amount).unwrap())
+            .sum();
+
+        let reserve = (total * bps).div_ceil(10_000 - bps) + transfers;
+        assert!(paid > total);
+        assert!(paid - total <= reserve);
+    }
+
+    #[test]
+    fn test_transfer_hook_is_supported() {
+        let data = mint_data(&[ExtensionType::TransferHook], |state| {
+            state.init_extension::<TransferHook>(true).unwrap();
+        });
+        assert!(validate_walien_mint(&data).is_ok());
+    }
+
+    #[test]
+    fn test_non_transferable_is_rejected() {
+        let data = mint_data(&[ExtensionType::NonTransferable], |state| {
+            state.init_extension::<NonTransferable>(true).unwrap();
+        });
+        assert_eq!(
+            validate_walien_mint(&data).unwrap_err(),
+            ErrorCode::UnsupportedWalienExtension.into()
+        );
+    }
+
+    #[test]
+    fn test_permanent_delegate_is_rejected() {
+        let data = mint_data(&[ExtensionType::PermanentDelegate], |state| {
+            state.init_extension::<PermanentDelegate>(true).unwrap();
+        });
+        assert_eq!(
+            validate_walien_mint(&data).unwrap_err(),
+            ErrorCode::UnsupportedWalienExtension.into()
+        );
+    }
+
+    #[test]
+    fn test_frozen_default_account_state_is_rejected() {
+        let frozen = mint_data(&[ExtensionType::DefaultAccountState], |state| {
+            let extension = state.init_extension::<DefaultAccountState>(true).unwrap();
+            extension.state = AccountState::Frozen as u8;
+        });
+        assert_eq!(
+            validate_walien_mint(&frozen).unwrap_err(),
+            ErrorCode::UnsupportedWalienExtension.into()
+        );
+
+        let initialized = mint_data(&[ExtensionType::DefaultAccountState], |state| {
+            let extension = state.init_extension::<DefaultAccountState>(true).unwrap();
+            extension.state = AccountState::Initialized as u8;
+        });
+        assert!(validate_walien_mint(&initialized).is_ok());
+    }
+}
@@ -1,29 +1,234 @@
 use crate::{
+    constants::USDC_DECIMALS,
     errors::ErrorCode,
-    orca_math::{compute_swap, sqrt_price_from_tick_index, SwapStepComputation},
-    state::GlobalConfig,
+    orca_math::{
+        compute_swap, sqrt_price_from_tick_index, tick_index_from_sqrt_price, SwapStepComputation,
+        MAX_FEE_RATE, MAX_SQRT_PRICE_X64, MIN_SQRT_PRICE_X64,
+    },
+    state::{GlobalConfig, LiquidityCurve, LiquidityRange},
 };
 use anchor_lang::prelude::*;
 
-pub fn calculate_swap_from_config(cfg: &GlobalConfig, amount: u64) -> Result<SwapStepComputation> {
+/// Curve invariants shared by `initialize_pool` and `update_pool_params`.
+pub fn validate_pool_params(
+    initial_sqrt_price_x64: u128,
+    tick_upper: i32,
+    liquidity: u128,
+    fee_bps: u16,
+) -> Result<()> {
+    require!(
+        (MIN_SQRT_PRICE_X64..=MAX_SQRT_PRICE_X64).contains(&initial_sqrt_price_x64),
+        ErrorCode::SqrtPriceOutOfBounds
+    );
+    let initial_tick = tick_index_from_sqrt_price(&initial_sqrt_price_x64);
+    require!(initial_tick < tick_upper, ErrorCode::InvalidTickIndex);
+    require!(liquidity > 0, ErrorCode::LiquidityZero);
+    require!(fee_bps <= MAX_FEE_RATE, ErrorCode::FeeRateTooHigh);
+    Ok(())
+}
+
+/// Converts quote units (USDC) into units of a pay mint with `decimals`, 1:1 in value.
+/// Charges round up and payouts round down; only mints with fewer decimals than USDC round.
+pub fn to_pay_amount(quote_amount: u64, decimals: u8, round_up: bool) -> Result<u64> {
+    if decimals >= USDC_DECIMALS {
+        let scale = 10u64
+            .checked_pow((decimals - USDC_DECIMALS) as u32)
+            .ok_or(ErrorCode::MultiplicationOverflow)?;
+        return Ok(quote_amount
+            .checked_mul(scale)
+            .ok_or(ErrorCode::MultiplicationOverflow)?);
+    }
+    let scale = 10u64.pow((USDC_DECIMALS - decimals) as u32);
+    let amount = quote_amount / scale;
+    if round_up && !quote_amount.is_multiple_of(scale) {
+        return Ok(amount + 1);
+    }
+    Ok(amount)
+}
+
+/// Fee rate for a swap right now; the clock is only read when an anti-snipe schedule is set.
+pub fn effective_fee_rate(cfg: &GlobalConfig) -> Result<u16> {
+    if !cfg.has_fee_schedule() {
+        return Ok(cfg.fee_bps);
+    }
+    Ok(cfg.fee_rate_at(Clock::get()?.unix_timestamp))
+}
+
+/// Ranges swaps walk through: the pool's curve, or the single `[price, tick_upper]` range.
+pub fn liquidity_ranges(
+    cfg: &GlobalConfig,
+    curve: Option<&LiquidityCurve>,
+) -> Result<Vec<LiquidityRange>> {
+    let ranges = match (cfg.has_liquidity_curve, curve) {
+        (true, Some(curve)) => curve.ranges.clone(),
+        (false, None) => vec![LiquidityRange {
+            tick_upper: cfg.tick_upper,
+            liquidity: cfg.liqudity,
+        }],
+        _ => return Err(ErrorCode::LiquidityCurveMismatch.into()),
+    };
+    require!(!ranges.is_empty(), ErrorCode::InvalidLiquidityCurve);
+    require!(
+        ranges.iter().all(|range| range.liquidity > 0),
+        ErrorCode::LiquidityZero
+    );
+    Ok(ranges)
+}
+
+pub fn calculate_swap_from_config(
+    cfg: &GlobalConfig,
+    curve: Option<&LiquidityCurve>,
+    amount: u64,
+) -> Result<SwapStepComputation> {
+    calculate_swap(cfg, curve, amount, true)
+}
+
+/// Same as `calculate_swap_from_config`, but `walien_amount` is the exact wALIEN output.
+pub fn calculate_exact_out_swap_from_config(
+    cfg: &GlobalConfig,
+    curve: Option<&LiquidityCurve>,
+    walien_amount: u64,
+) -> Result<SwapStepComputation> {
+    calculate_swap(cfg, curve, walien_amount, false)
+}
+
+/// Price after a refund returns `walien_amount` to the curve: the reverse (`a_to_b = true`)
+/// swap of the allocation, without fee, stepping down the ranges and stopping at
+/// `floor_sqrt_price_x64`.
+///
+/// Liquidity is constant within a range, so the price only depends on the total wALIEN
+/// returned and refunds may happen in any order. Rounding is in favor of the pool, so
+/// refunding every buy lands at or slightly above the price before those buys.
+pub fn calculate_refund_price_from_config(
+    cfg: &GlobalConfig,
+    curve: Option<&LiquidityCurve>,
+    walien_amount: u64,
+) -> Result<u128> {
+    if walien_amount == 0 || cfg.initial_sqrt_price_x64 <= cfg.floor_sqrt_price_x64 {
+        return Ok(cfg.initial_sqrt_price_x64);
+    }
+
+    let ranges = liquidity_ranges(cfg, curve)?;
+    let a_to_b = true;
+
+    let mut sqrt_price = cfg.initial_sqrt_price_x64;
+    let mut amount_remaining = walien_amount;
+    for (i, range) in ranges.iter().enumerate().rev() {
+        let sqrt_price_lower = if i == 0 {
+            cfg.floor_sqrt_price_x64
+        } else {
+            sqrt_price_from_tick_index(ranges[i - 1].tick_upper).max(cfg.floor_sqrt_price_x64)
+        };
+        if sqrt_price_lower >= sqrt_price {
+            continue;
+        }
+
+        let step = compute_swap(
+            amount_remaining,
+            0,
+            range.liquidity,
+            sqrt_price,
+            sqrt_price_lower,
+            true,
+            a_to_b,
+        )?;
+        amount_remaining = amount_remaining
+            .checked_sub(step.amount_in)
+            .ok_or(ErrorCode::AmountCalcOverflow)?;
+        sqrt_price = step.next_price;
+        if amount_remaining == 0 || sqrt_price != sqrt_price_lower {
+            break;
+        }
+    }
+    Ok(sqrt_price)
+}
+
+/// Walks the ranges upwards like Whirlpool's swap loop crosses ticks, one `compute_swap`
+/// step per range, and returns the aggregated result.
+fn calculate_swap(
+    cfg: &GlobalConfig,
+    curve: Option<&LiquidityCurve>,
+    amount: u64,
+    amount_specified_is_input: bool,
+) -> Result<SwapStepComputation> {
     if amount == 0 {
         return Err(ErrorCode::ZeroTradableAmount.into());
     }
 
-    if cfg.liqudity == 0 {
-        return Err(ErrorCode::LiquidityZero.into());
-    }
-    let fee_rate = cfg.fee_bps as u32;
-    let amount_specified_is_input = true;
+    let ranges = liquidity_ranges(cfg, curve)?;
+    let fee_rate = effective_fee_rate(cfg)? as u32;
     let a_to_b = false;
 
-    Ok(compute_swap(
-        amount,
-        fee_rate,
-        cfg.liqudity as u128,
-        cfg.initial_sqrt_price_x64,
-        sqrt_price_from_tick_index(cfg.tick_upper),
-        amount_specified_is_input,
-        a_to_b,
-    )?)
+    // Start in the range holding the current price, the last one once the curve is exhausted
+    let start = ranges
+        .iter()
+        .position(|range| sqrt_price_from_tick_index(range.tick_upper) > cfg.initial_sqrt_price_x64)
+        .unwrap_or(ranges.len() - 1);
+
+    let mut result = SwapStepComputation {
+        amount_in: 0,
+        amount_out: 0,
+        next_price: cfg.initial_sqrt_price_x64,
+        fee_amount: 0,
+    };
+    let mut amount_remaining = amount;
+    for range in ranges[start..].iter() {
+        let sqrt_price_target = sqrt_price_from_tick_index(range.tick_upper);
+        let step = compute_swap(
+            amount_remaining,
+            fee_rate,
+            range.liquidity,
+            result.next_price,
+            sqrt_price_target,
+            amount_specified_is_input,
+            a_to_b,
+        )?;
+
+        result.amount_in = result
+            .amount_in
+            .checked_add(step.amount_in)
+            .ok_or(ErrorCode::AmountCalcOverflow)?;
+        result.amount_out = result
+            .amount_out
+            .checked_add(step.amount_out)
+            .ok_or(ErrorCode::AmountCalcOverflow)?;
+        result.fee_amount = result
+            .fee_amount
+            .checked_add(step.fee_amount)
+            .ok_or(ErrorCode::AmountCalcOverflow)?;
+        result.next_price = step.next_price;
+
+        let consumed = if amount_specified_is_input {
+            step.amount_in
+                .checked_add(step.fee_amount)
+                .ok_or(ErrorCode::AmountCalcOverflow)?
+        } else {
+            step.amount_out
+        };
+        amount_remaining = amount_remaining
+            .checked_sub(consumed)
+            .ok_or(ErrorCode::AmountCalcOverflow)?;
+        if amount_remaining == 0 || step.next_price != sqrt_price_target {
+            break;
+        }
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pay_amount_normalizes_decimals() {
+        assert_eq!(to_pay_amount(1_234_567, 6, true).unwrap(), 1_234_567);
+        assert_eq!(
+            to_pay_amount(1_234_567, 18, false).unwrap(),
+            1_234_567_000_000_000_000
+        );
+        assert_eq!(to_pay_amount(1_234_567, 2, true).unwrap(), 124);
+        assert_eq!(to_pay_amount(1_234_567, 2, false).unwrap(), 123);
+        assert_eq!(to_pay_amount(1_230_000, 2, true).unwrap(), 123);
+        assert!(to_pay_amount(u64::MAX, 18, false).is_err());
+    }
 }